@@ -0,0 +1,89 @@
+use core::fmt;
+
+use super::Symbols;
+
+// generated code is kept per section until the whole file has been compiled
+pub struct Assembly {
+    sections: Vec<(&'static str, String)>,
+    current: &'static str,
+    exports: Vec<(String, String)>,
+    imports: Vec<String>,
+}
+
+impl Default for Assembly {
+    fn default() -> Self {
+        Self {
+            sections: Vec::new(),
+            current: ".text",
+            exports: Vec::new(),
+            imports: Vec::new(),
+        }
+    }
+}
+
+impl Assembly {
+    pub(crate) fn section(&mut self, name: &'static str) -> &mut String {
+        let index = match self.sections.iter().position(|(n, _)| *n == name) {
+            Some(index) => index,
+            None => {
+                self.sections.push((name, String::new()));
+                self.sections.len() - 1
+            }
+        };
+        &mut self.sections[index].1
+    }
+
+    pub(crate) fn current(&self) -> &'static str {
+        self.current
+    }
+
+    pub(crate) fn switch(&mut self, name: &'static str) {
+        self.current = name;
+    }
+
+    // data goes to its own section unless another data section was chosen by `in section`
+    pub(crate) fn data_section(&self, default: &'static str) -> &'static str {
+        if self.current == ".text" {
+            default
+        } else {
+            self.current
+        }
+    }
+
+    pub(crate) fn export(&mut self, label: &str, location: String) {
+        if !self.exports.iter().any(|(l, _)| l == label) {
+            self.exports.push((label.to_string(), location));
+        }
+    }
+
+    pub(crate) fn import(&mut self, label: &str) {
+        if !self.imports.iter().any(|l| l == label) {
+            self.imports.push(label.to_string());
+        }
+    }
+
+    pub fn warnings(&self, symbols: &Symbols) -> Vec<String> {
+        self.exports
+            .iter()
+            .filter(|(label, _)| !symbols.is_label(label))
+            .map(|(label, location)| format!("warning [{}] : exported label '{}' is never defined", location, label))
+            .collect()
+    }
+}
+
+impl fmt::Display for Assembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, ".intel_syntax noprefix")?;
+        for (label, _) in &self.exports {
+            writeln!(f, ".globl {}", label)?;
+        }
+        for label in &self.imports {
+            writeln!(f, ".extern {}", label)?;
+        }
+        for (name, code) in &self.sections {
+            writeln!(f, ".section {}", name)?;
+            write!(f, "{}", code)?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::data::sentence::Keyword;
 
-use super::{AsmError, Object, Preposition, PrepositionPhrases, Sentence, Verb, TokenLocation};
+use super::{AsmError, Assembly, Data, Object, Preposition, PrepositionPhrases, Register, RegisterClass, Sentence, Verb, TokenLocation};
 
 macro_rules! check_if {
     ($cond: expr, $loc: expr, $msg: expr) => {
@@ -12,150 +12,944 @@ macro_rules! check_if {
     };
 }
 
-pub fn codegen<'a>(s:Sentence<'a>) -> Result<String, AsmError<'a>> {
+pub fn codegen<'a>(s: Sentence<'a>, code: &mut Assembly) -> Result<(), AsmError<'a>> {
+    let (section, asm) = match s {
+        Sentence::DataDefinition(label, data) => {
+            // bss only holds zeros, so it can only reserve space
+            check_if!(code.current() != ".bss" || matches!(data, Data::Reserve(..)), label.1, "section bss can only reserve space; define initialised data in section data".to_string());
+            let (section, asm) = data_definition(label.0, data);
+            (code.data_section(section), asm)
+        }
+        Sentence::LabelDefinition(l) => {
+            (code.current(), format!("{}:\n", l.0))
+        }
+        Sentence::Export(label) => {
+            code.export(label.0, label.1.to_string());
+            return Ok(());
+        }
+        Sentence::Import(label) => {
+            code.import(label.0);
+            return Ok(());
+        }
+        Sentence::Section(name) => {
+            code.switch(name.0);
+            return Ok(());
+        }
+        s => (code.current(), instruction(s)?),
+    };
+    code.section(section).push_str(&asm);
+    Ok(())
+}
+
+fn instruction<'a>(s: Sentence<'a>) -> Result<String, AsmError<'a>> {
     match s {
         Sentence::Sentence {
             verb,
             prepositional_phrases,
             object: Some(obj),
         } => match verb.0 {
-            Verb::Add => add_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Substract => sub_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Multiply => mul_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Divide => div_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Move => mov_instruction(verb.1, obj, prepositional_phrases),
-            Verb::And | Verb::Or | Verb::Xor => logical_binary_instruction(verb, obj, prepositional_phrases),
-            Verb::Not => not_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Negate => negate_instruction(verb.1, obj, prepositional_phrases),
-            Verb::ShiftLeft => shl_instruction(verb.1, obj, prepositional_phrases),
-            Verb::ShiftRight => shr_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Call => call_instruction(verb.1, obj, prepositional_phrases),
-            Verb::Compare => cmp_instruction(verb.1, obj, prepositional_phrases),
-            _ => Err(AsmError::SyntaxError(verb.1, format!("something is wrong?"))),
+            Verb::Add => add_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Substract => sub_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Multiply => mul_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Divide => div_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Move => mov_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::And | Verb::Or | Verb::Xor => logical_binary_instruction(*verb, *obj, prepositional_phrases),
+            Verb::Not => not_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Negate => negate_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::ShiftLeft | Verb::ShiftRight | Verb::RotateLeft | Verb::RotateRight => shift_instruction(*verb, *obj, prepositional_phrases),
+            Verb::Call => call_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Compare => cmp_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Increment | Verb::Decrement => inc_dec_instruction(*verb, *obj, prepositional_phrases),
+            Verb::Test | Verb::Check => test_instruction(*verb, *obj, prepositional_phrases),
+            Verb::BitTest | Verb::BitTestAndSet | Verb::BitTestAndReset | Verb::BitTestAndComplement => bit_test_instruction(*verb, *obj, prepositional_phrases),
+            Verb::Load => lea_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Convert => convert_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Copy => copy_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Fill => fill_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Scan => scan_instruction(verb.1, Some(*obj), prepositional_phrases),
+            Verb::Set => set_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Exchange => xchg_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::CompareAndExchange => cmpxchg_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::FetchAndAdd => xadd_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Push => push_instruction(verb.1, *obj, prepositional_phrases),
+            Verb::Pop => pop_instruction(verb.1, *obj, prepositional_phrases),
+            _ => Err(AsmError::SyntaxError(verb.1, "something is wrong?".to_string())),
         },
         Sentence::Sentence {
             verb,
             prepositional_phrases,
             object: None,
-        } => vi_instructions(verb, prepositional_phrases),
-        Sentence::LabelDefinition(l) => Ok(format!("{}:\n", l.0)),
-        Sentence::Null =>  Ok(format!("\n"))
-        // _ => Err(AsmError::SyntaxError(format!("something is wrong"))),
+        } => match verb.0 {
+            Verb::Scan => scan_instruction(verb.1, None, prepositional_phrases),
+            _ => vi_instructions(*verb, prepositional_phrases),
+        },
+        Sentence::List { verb, objects } => list_instructions(*verb, objects),
+        Sentence::Atomic(atomic) => atomic_instruction(*atomic),
+        Sentence::Null =>  Ok("\n".to_string()),
+        Sentence::Note(note) => Ok(format!("\t# {}\n", note)),
+        Sentence::LabelDefinition(l) | Sentence::DataDefinition(l, _) | Sentence::Export(l) | Sentence::Import(l) => {
+            Err(AsmError::SyntaxError(l.1, "this sentence is not an instruction".to_string()))
+        }
+        Sentence::Section(s) => Err(AsmError::SyntaxError(s.1, "this sentence is not an instruction".to_string())),
     }
 }
 
-fn as_processer<'a>(pps: &PrepositionPhrases<'a>) -> Result<&'a str, AsmError<'a>> {
-    match pps.consume(Preposition::As) {
-        Some((Object::Keyword(Keyword::DoublePrecisionFloat), _)) => Ok("sd"),
-        Some((Object::Keyword(Keyword::SinglePrecisionFloat), _)) => Ok("ss"),
-        None => Ok(""),
-        Some((_, loc)) => Err(AsmError::SyntaxError::<'a>(loc, format!("as only takes key word")))
+fn data_directive(size: usize) -> &'static str {
+    match size {
+        8 => ".byte",
+        16 => ".word",
+        32 => ".long",
+        _ => ".quad",
+    }
+}
+
+// strings are read-only, other data is writable and reserved space is zero-filled
+fn data_definition(label: &str, data: Data) -> (&'static str, String) {
+    match data {
+        Data::String(literal) => (".rodata", format!("{label}:\n\t.string {literal}\n")),
+        Data::Values(size, values) => {
+            let values = values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(", ");
+            (".data", format!("\t.balign {align}\n{label}:\n\t{directive} {values}\n", align = size / 8, directive = data_directive(size)))
+        }
+        Data::Reserve(size, count) => {
+            (".bss", format!("\t.balign {align}\n{label}:\n\t.zero {bytes}\n", align = size / 8, bytes = count as usize * size / 8))
+        }
+    }
+}
+
+fn as_processer<'a>(pps: &PrepositionPhrases<'a>) -> Result<&'static str, AsmError<'a>> {
+    pps.consume(Preposition::As).map_or(Ok(""), float_suffix)
+}
+
+fn float_suffix<'a>(o: (Object<'a>, TokenLocation<'a>)) -> Result<&'static str, AsmError<'a>> {
+    match o {
+        (Object::Keyword(Keyword::DoublePrecisionFloat), _) => Ok("sd"),
+        (Object::Keyword(Keyword::SinglePrecisionFloat), _) => Ok("ss"),
+        (Object::Keyword(Keyword::PackedDoubles), _) => Ok("pd"),
+        (Object::Keyword(Keyword::PackedSingles), _) => Ok("ps"),
+        (_, loc) => Err(AsmError::SyntaxError(loc, "expected single-precision-float, double-precision-float, packed-singles or packed-doubles".to_string())),
+    }
+}
+
+// an instruction touching memory needs an operand that tells its size: `move 1 to qword [rax]`.
+// a bare label is a memory operand too.
+fn check_size<'a>(loc: TokenLocation<'a>, operands: &[&Object<'a>]) -> Result<(), AsmError<'a>> {
+    let memory = operands.iter().any(|o| matches!(o, Object::Mem(_) | Object::Label(_)));
+    let sized = operands.iter().any(|o| o.size().is_some());
+    check_if!(!memory || sized, loc, "operand size is ambiguous; use byte, word, dword or qword".to_string());
+    Ok(())
+}
+
+// operand legality shared by the two-operand integer instructions.
+// errors point at the operand at fault rather than at the verb.
+fn check_operands<'a>(dest: &(Object<'a>, TokenLocation<'a>), src: &(Object<'a>, TokenLocation<'a>), imm64: bool) -> Result<(), AsmError<'a>> {
+    check_destination(dest)?;
+    check_integer(src)?;
+    let memory = |o: &Object| matches!(o, Object::Mem(_) | Object::Label(_));
+    check_if!(!(memory(&dest.0) && memory(&src.0)), src.1, "memory to memory is not allowed; use a register in between".to_string());
+    check_high_byte(dest, src)?;
+    match (dest.0.size(), &src.0) {
+        (size, Object::Imm(imm)) => check_immediate(*imm, src.1, size, imm64 && matches!(dest.0, Object::Reg(_)))?,
+        (Some(dest_size), src_obj) => {
+            if let Some(src_size) = src_obj.size() {
+                check_if!(dest_size == src_size, src.1, format!("{} is {}-bit but {} is {}-bit", src.0, src_size, dest.0, dest_size));
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+// ah, bh, ch and dh can't be encoded in an instruction with a REX prefix
+fn check_high_byte<'a>(dest: &(Object<'a>, TokenLocation<'a>), src: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    let high_byte = |o: &Object| matches!(o, Object::Reg(reg) if reg.is_high_byte());
+    let rex = |o: &Object| match o {
+        Object::Reg(reg) => reg.needs_rex() || reg.width() == 64,
+        Object::Mem(mem) => mem.needs_rex(),
+        _ => false,
+    };
+    check_if!(!(high_byte(&dest.0) && rex(&src.0) || rex(&dest.0) && high_byte(&src.0)), src.1, format!("{} and {} can't be used together", dest.0, src.0));
+    Ok(())
+}
+
+fn check_destination<'a>(dest: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    check_if!(!matches!(dest.0, Object::Imm(_)), dest.1, "an immediate can't be a destination".to_string());
+    check_integer(dest)
+}
+
+fn check_integer<'a>(o: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    match &o.0 {
+        Object::Reg(reg) if reg.class() != RegisterClass::GeneralPurpose => Err(AsmError::SyntaxError(o.1, format!("{} can't be used in integer instructions", reg))),
+        Object::Keyword(_) => Err(AsmError::SyntaxError(o.1, "expected a register, memory or immediate".to_string())),
+        _ => Ok(()),
+    }
+}
+
+// immediates are sign-extended from 32 bits except for `mov r64, imm64`
+fn check_immediate<'a>(imm: i128, loc: TokenLocation<'a>, size: Option<usize>, imm64: bool) -> Result<(), AsmError<'a>> {
+    let fits = match size {
+        Some(8) => (-128..=255).contains(&imm),
+        Some(16) => (-32768..=65535).contains(&imm),
+        Some(32) => (i128::from(i32::MIN)..=i128::from(u32::MAX)).contains(&imm),
+        Some(64) if imm64 => (i128::from(i64::MIN)..=i128::from(u64::MAX)).contains(&imm),
+        _ => i32::try_from(imm).is_ok(),
+    };
+    let limit = match size {
+        Some(size @ (8 | 16 | 32)) => format!("{} bits", size),
+        Some(64) if imm64 => "64 bits".to_string(),
+        _ => "32 bits (sign-extended)".to_string(),
+    };
+    check_if!(fits, loc, format!("immediate {} doesn't fit in {}", imm, limit));
+    Ok(())
+}
+
+// sd/ss work on an xmm register with an xmm register or memory holding one float.
+// pd/ps also take ymm registers, and their memory operands are left unsized.
+fn check_float<'a>(suffix: &str, reg: &(Object<'a>, TokenLocation<'a>), other: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    let packed = matches!(suffix, "pd" | "ps");
+    let class = match reg.0 {
+        Object::Reg(reg) if packed && reg.class() == RegisterClass::Ymm => RegisterClass::Ymm,
+        _ => RegisterClass::Xmm,
+    };
+    let expected = if packed { "an xmm or ymm register" } else { "an xmm register" };
+    check_if!(matches!(reg.0, Object::Reg(reg) if reg.class() == class), reg.1, format!("floating point instructions need {}", expected));
+    match &other.0 {
+        Object::Reg(reg) => check_if!(reg.class() == class, other.1, format!("{} can't be used with {}", reg, class_name(class))),
+        Object::Mem(mem) => {
+            let size = match suffix {
+                "sd" => Some(64),
+                "ss" => Some(32),
+                _ => None,
+            };
+            check_if!(mem.size().is_none() || mem.size() == size, other.1, format!("{} doesn't hold a {}", other.0, float_name(suffix)));
+        }
+        _ => return Err(AsmError::SyntaxError(other.1, format!("floating point instructions need {} or memory", expected))),
     }
+    Ok(())
 }
 
-fn add_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn class_name(class: RegisterClass) -> &'static str {
+    match class {
+        RegisterClass::Ymm => "ymm registers",
+        _ => "xmm registers",
+    }
+}
+
+fn float_name(suffix: &str) -> &'static str {
+    match suffix {
+        "sd" => "double-precision-float",
+        "ss" => "single-precision-float",
+        "pd" => "packed-doubles",
+        _ => "packed-singles",
+    }
+}
+
+fn check_arithmetic<'a>(suffix: &str, dest: &(Object<'a>, TokenLocation<'a>), src: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    if suffix.is_empty() {
+        check_operands(dest, src, false)
+    } else {
+        check_float(suffix, dest, src)
+    }
+}
+
+// add xmm1 to ymm0 as packed-doubles => vaddpd ymm0, ymm0, ymm1
+fn arithmetic<'a>(v: &str, suffix: &str, dest: &Object<'a>, src: &Object<'a>) -> String {
+    match dest {
+        Object::Reg(reg) if reg.class() == RegisterClass::Ymm => format!("\tv{v}{suffix} {dest}, {dest}, {src}\n"),
+        _ => format!("\t{v}{suffix} {dest}, {src}\n"),
+    }
+}
+
+fn add_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have(Preposition::To), loc, "add instruction requires 'to' phrase".to_string());
-    let to = pps.consume(Preposition::To).unwrap().0;
+    let to = pps.consume(Preposition::To).unwrap();
     let suffix = as_processer(&pps)?;
     check_if!(pps.have_no_phrases(), loc, "add instruction accepts just 'to' and 'as'".to_string());
-    Ok(format!("\tadd{suffix} {dest}, {src}\n", suffix = suffix, dest = to, src = o.0))
+    check_arithmetic(suffix, &to, &o)?;
+    check_size(loc, &[&to.0, &o.0])?;
+    Ok(arithmetic("add", suffix, &to.0, &o.0))
 }
-fn cmp_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps:  PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn cmp_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps:  PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have(Preposition::To), loc, "compare instruction requires 'to' phrase".to_string());
-    let to = pps.consume(Preposition::To).unwrap().0;
+    let to = pps.consume(Preposition::To).unwrap();
     let suffix = as_processer(&pps)?;
     check_if!(pps.have_no_phrases(), loc, "compare instruction accepts just 'to' and 'as'".to_string());
-    Ok(format!("\tcmp{suffix} {dest}, {src}\n", suffix = suffix, dest = o.0, src = to))
+    check_if!(!matches!(suffix, "pd" | "ps"), loc, "packed floats can't be compared".to_string());
+    check_arithmetic(suffix, &o, &to)?;
+    check_size(loc, &[&o.0, &to.0])?;
+    match suffix {
+        // ucomisd sets the flags like an unsigned compare, so follow it with below/above
+        "" => Ok(format!("\tcmp {dest}, {src}\n", dest = o.0, src = to.0)),
+        _ => Ok(format!("\tucomi{suffix} {dest}, {src}\n", dest = o.0, src = to.0)),
+    }
+}
+
+// test eax with eax, check bits of eax with 1
+fn test_instruction<'a>(verb: (Verb, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let dest = match (verb.0, o) {
+        (Verb::Check, (Object::Keyword(Keyword::Bits), _)) => {
+            check_if!(pps.have(Preposition::Of), verb.1, "check instruction requires 'of' phrase".to_string());
+            pps.consume(Preposition::Of).unwrap()
+        }
+        (Verb::Check, (_, loc)) => return Err(AsmError::SyntaxError(loc, "check instruction looks like 'check bits of eax with 1'".to_string())),
+        (_, o) => o,
+    };
+    check_if!(pps.have(Preposition::With), verb.1, "test instruction requires 'with' phrase".to_string());
+    let with = pps.consume(Preposition::With).unwrap();
+    check_if!(pps.have_no_phrases(), verb.1, "test instruction accepts just 'with'".to_string());
+    check_if!(!matches!(dest.0, Object::Imm(_)), dest.1, "test instruction can't test an immediate".to_string());
+    check_operands(&dest, &with, false)?;
+    check_size(verb.1, &[&dest.0, &with.0])?;
+    Ok(format!("\ttest {dest}, {src}\n", dest = dest.0, src = with.0))
+}
+
+// bit-test 3 of eax => bt eax, 3
+fn bit_test_instruction<'a>(verb: (Verb, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let v = match verb.0 {
+        Verb::BitTest => "bt",
+        Verb::BitTestAndSet => "bts",
+        Verb::BitTestAndReset => "btr",
+        _ => "btc",
+    };
+    check_if!(pps.have(Preposition::Of), verb.1, "bit test instructions require 'of' phrase".to_string());
+    let (dest, dest_loc) = pps.consume(Preposition::Of).unwrap();
+    check_if!(pps.have_no_phrases(), verb.1, "bit test instructions accept just 'of'".to_string());
+    check_if!(matches!(dest, Object::Reg(_) | Object::Mem(_)), dest_loc, "bit test instructions need a register or memory".to_string());
+    match o.0 {
+        Object::Imm(bit) => {
+            check_if!((0..256).contains(&bit), o.1, "bit index must be between 0 and 255".to_string());
+            check_size(verb.1, &[&dest])?;
+        }
+        Object::Reg(_) => check_if!(dest.size().is_none_or(|size| Some(size) == o.0.size()), o.1, format!("bit index must be as wide as {}", dest)),
+        _ => return Err(AsmError::SyntaxError(o.1, "bit index must be an immediate or a register".to_string())),
+    }
+    check_if!(!matches!(dest.size().or(o.0.size()), Some(8)), dest_loc, "bit test instructions don't take byte operands".to_string());
+    Ok(format!("\t{v} {dest}, {src}\n", src = o.0))
 }
 
-fn sub_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn sub_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have(Preposition::From), loc, "substract instruction requires 'from' phrase".to_string());
-    let from = pps.consume(Preposition::From).unwrap().0;
+    let from = pps.consume(Preposition::From).unwrap();
     let suffix = as_processer(&pps)?;
     check_if!(pps.have_no_phrases(), loc, "substract instruction accepts just 'from' and 'as'".to_string());
-    Ok(format!("\tsub{suffix} {dest}, {src}\n", suffix = suffix, dest = from, src = o.0))
-
+    check_arithmetic(suffix, &from, &o)?;
+    check_size(loc, &[&from.0, &o.0])?;
+    Ok(arithmetic("sub", suffix, &from.0, &o.0))
 }
 
-fn mul_instruction<'a>(loc:TokenLocation<'a>, o:Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn mul_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have(Preposition::By), loc, "multiply instruction requires 'by' phrase".to_string());
-    let by = pps.consume(Preposition::By).unwrap().0;
-    check_if!(pps.have_no_phrases(), loc, "multiply instruction accepts just 'by'".to_string());
-    Ok(format!("\timul {dest}, {src}\n", dest = o.0, src = by))
+    let by = pps.consume(Preposition::By).unwrap();
+    let signedness = signedness(&pps);
+    let suffix = if signedness.is_some() { "" } else { as_processer(&pps)? };
+    check_if!(pps.have_no_phrases(), loc, "multiply instruction accepts just 'by' and 'as'".to_string());
+    if !suffix.is_empty() {
+        check_float(suffix, &o, &by)?;
+        return Ok(arithmetic("mul", suffix, &o.0, &by.0));
+    }
+    // multiply rax by rcx as unsigned => mul rcx, leaving the high half in rdx
+    if matches!(signedness, Some(Keyword::Unsigned)) {
+        check_accumulator(&o)?;
+        let by = sized_like(by, &o.0);
+        check_operands(&o, &by, false)?;
+        check_if!(matches!(by.0, Object::Reg(_) | Object::Mem(_)), by.1, "unsigned multiply needs a register or memory multiplier".to_string());
+        return Ok(format!("\tmul {src}\n", src = by.0));
+    }
+    check_if!(matches!(o.0, Object::Reg(_)) && matches!(o.0.size(), Some(16 | 32 | 64)), o.1, "multiply instruction needs a 16, 32 or 64-bit register destination".to_string());
+    check_operands(&o, &by, false)?;
+    Ok(format!("\timul {dest}, {src}\n", dest = o.0, src = by.0))
 }
 
-fn shr_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
-    check_if!(pps.have(Preposition::By), loc, "shift_right instruction requires 'By' phrase".to_string());
-    let by = pps.consume(Preposition::By).unwrap().0;
-    check_if!(pps.have_no_phrases(), loc, "shift_right instruction accepts just 'by'".to_string());
-    Ok(format!("\tshr {dest}, {src}\n", dest = o.0, src = by))
+// shift-right eax by 3 as signed => sar eax, 3
+// rotate-left eax by cl with carry => rcl eax, cl
+// shift-left eax by cl with ebx => shld eax, ebx, cl
+fn shift_instruction<'a>(verb: (Verb, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let name = match verb.0 {
+        Verb::ShiftLeft => "shift-left",
+        Verb::ShiftRight => "shift-right",
+        Verb::RotateLeft => "rotate-left",
+        _ => "rotate-right",
+    };
+    check_if!(pps.have(Preposition::By), verb.1, format!("{} instruction requires 'by' phrase", name));
+    let by = pps.consume(Preposition::By).unwrap();
+    let signed = match (&verb.0, pps.consume(Preposition::As)) {
+        (Verb::ShiftRight, Some((Object::Keyword(Keyword::Signed), _))) => true,
+        (Verb::ShiftRight, Some((Object::Keyword(Keyword::Unsigned), _))) | (_, None) => false,
+        (_, Some((_, loc))) => return Err(AsmError::SyntaxError(loc, "only shift-right can be signed or unsigned".to_string())),
+    };
+    let with = pps.consume(Preposition::With);
+    check_if!(pps.have_no_phrases(), verb.1, format!("{} instruction accepts just 'by', 'with' and 'as'", name));
+    check_size(verb.1, &[&o.0])?;
+    check_destination(&o)?;
+    match by.0 {
+        Object::Imm(count) => check_if!((0..256).contains(&count), by.1, "shift count must be between 0 and 255".to_string()),
+        Object::Reg(Register::CL) => (),
+        _ => return Err(AsmError::SyntaxError(by.1, "shift count must be an immediate or cl".to_string())),
+    }
+    let v = match (&verb.0, &with) {
+        (Verb::RotateLeft, Some((Object::Keyword(Keyword::Carry), _))) => "rcl",
+        (Verb::RotateRight, Some((Object::Keyword(Keyword::Carry), _))) => "rcr",
+        (Verb::RotateLeft, None) => "rol",
+        (Verb::RotateRight, None) => "ror",
+        (Verb::ShiftLeft | Verb::ShiftRight, Some(with)) => {
+            // double precision shifts fill the vacated bits from another register
+            check_if!(!signed, verb.1, "double precision shifts can't be signed".to_string());
+            check_if!(matches!(with.0, Object::Reg(_)), with.1, "double precision shifts take the bits from a register".to_string());
+            check_if!(matches!(o.0.size(), Some(16 | 32 | 64)), o.1, "double precision shifts need a 16, 32 or 64-bit destination".to_string());
+            check_operands(&o, with, false)?;
+            let v = if matches!(verb.0, Verb::ShiftLeft) { "shld" } else { "shrd" };
+            return Ok(format!("\t{v} {dest}, {src}, {count}\n", dest = o.0, src = with.0, count = by.0));
+        }
+        (Verb::ShiftLeft, None) => "shl",
+        (Verb::ShiftRight, None) if signed => "sar",
+        (Verb::ShiftRight, None) => "shr",
+        (_, Some((_, loc))) => return Err(AsmError::SyntaxError(*loc, "rotate instructions only take 'with carry'".to_string())),
+        _ => unreachable!(),
+    };
+    Ok(format!("\t{v} {dest}, {src}\n", dest = o.0, src = by.0))
+}
+
+// `as signed` or `as unsigned`, leaving any other 'as' phrase alone
+fn signedness(pps: &PrepositionPhrases) -> Option<Keyword> {
+    match pps.consume(Preposition::As) {
+        Some((Object::Keyword(key @ (Keyword::Signed | Keyword::Unsigned)), _)) => Some(key),
+        Some(other) => {
+            pps.insert(Preposition::As, other);
+            None
+        }
+        None => None,
+    }
 }
 
-fn shl_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
-    check_if!(pps.have(Preposition::By), loc, "shift_left instruction requires 'By' phrase".to_string());
-    let by = pps.consume(Preposition::By).unwrap().0;
-    check_if!(pps.have_no_phrases(), loc, "shift_left instruction accepts just 'by'".to_string());
-    Ok(format!("\tshl {dest}, {src}\n", dest = o.0, src = by))
+// `mul [rcx]` doesn't say its size, so unsized memory takes the size of the accumulator
+fn sized_like<'a>(o: (Object<'a>, TokenLocation<'a>), like: &Object<'a>) -> (Object<'a>, TokenLocation<'a>) {
+    match (o, like.size()) {
+        ((Object::Mem(mem), loc), Some(size)) if mem.size().is_none() => (Object::Mem(mem.sized(size)), loc),
+        (o, _) => o,
+    }
 }
 
-fn div_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
-    check_if!(pps.have_no_phrases(), loc, "divide instruction doesn't accept any phrases".to_string());
-    Ok(format!("\tidiv {dest}\n", dest = o.0))
+// one-operand mul and div work on al, ax, eax or rax
+fn check_accumulator<'a>(o: &(Object<'a>, TokenLocation<'a>)) -> Result<(), AsmError<'a>> {
+    check_if!(matches!(o.0, Object::Reg(reg) if reg.parent() == Register::RAX && !reg.is_high_byte()), o.1, "expected al, ax, eax or rax".to_string());
+    Ok(())
 }
 
-fn not_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn div_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let signedness = signedness(&pps);
+    // divide xmm0 by xmm1 as double-precision-float => divsd xmm0, xmm1
+    let suffix = if signedness.is_some() { "" } else { as_processer(&pps)? };
+    if !suffix.is_empty() {
+        check_if!(pps.have(Preposition::By), loc, "floating point divide instruction requires 'by' phrase".to_string());
+        let by = pps.consume(Preposition::By).unwrap();
+        check_if!(pps.have_no_phrases(), loc, "floating point divide instruction accepts just 'by' and 'as'".to_string());
+        check_float(suffix, &o, &by)?;
+        return Ok(arithmetic("div", suffix, &o.0, &by.0));
+    }
+    let v = if matches!(signedness, Some(Keyword::Unsigned)) { "div" } else { "idiv" };
+    let Some(by) = pps.consume(Preposition::By) else {
+        // divide rcx => idiv rcx, with rdx:rax prepared by hand
+        check_if!(pps.have_no_phrases(), loc, "divide instruction without 'by' accepts just 'as'".to_string());
+        check_size(loc, &[&o.0])?;
+        check_destination(&o)?;
+        return Ok(format!("\t{v} {dest}\n", dest = o.0));
+    };
+    let remainder = match pps.consume(Preposition::Keep) {
+        Some((Object::Keyword(Keyword::Remainder), keep_loc)) => {
+            check_if!(pps.have(Preposition::In), keep_loc, "keep remainder requires 'in' phrase".to_string());
+            pps.consume(Preposition::In)
+        }
+        Some((_, keep_loc)) => return Err(AsmError::SyntaxError(keep_loc, "divide instruction can only keep remainder".to_string())),
+        None => None,
+    };
+    check_if!(pps.have_no_phrases(), loc, "divide instruction accepts just 'by', 'as' and 'keep remainder in'".to_string());
+
+    // divide rax by rcx as unsigned => xor edx, edx; div rcx
+    // divide eax by ecx => cdq; idiv ecx
+    check_accumulator(&o)?;
+    let by = sized_like(by, &o.0);
+    check_operands(&o, &by, false)?;
+    check_if!(matches!(by.0, Object::Reg(_) | Object::Mem(_)), by.1, "divisor must be a register or memory".to_string());
+    check_if!(!matches!(by.0, Object::Reg(reg) if matches!(reg.parent(), Register::RAX | Register::RDX)), by.1, format!("{} is overwritten before dividing; use another register", by.0));
+    // the setup overwrites rdx, or ax for bytes, which a memory divisor may address through
+    let clobbered = if o.0.size() == Some(8) { Register::AX } else { Register::RDX };
+    check_if!(!uses(&by.0, clobbered), by.1, format!("{} is overwritten before dividing; address the divisor through another register", clobbered));
+    let Object::Reg(dividend) = o.0 else { unreachable!() };
+    let (setup, high) = match (dividend.width(), v) {
+        (8, "div") => ("movzx ax, al", Register::AH),
+        (8, _) => ("cbw", Register::AH),
+        (width, "div") => ("xor edx, edx", Register::RDX.with_width(width).unwrap()),
+        (16, _) => ("cwd", Register::DX),
+        (32, _) => ("cdq", Register::EDX),
+        _ => ("cqo", Register::RDX),
+    };
+    let mut code = format!("\t{setup}\n\t{v} {src}\n", src = by.0);
+    if let Some(remainder) = remainder {
+        check_if!(matches!(remainder.0, Object::Reg(reg) if reg.width() == dividend.width() && reg.class() == RegisterClass::GeneralPurpose), remainder.1, format!("remainder must be kept in a {}-bit register", dividend.width()));
+        check_if!(!matches!(remainder.0, Object::Reg(reg) if reg.parent() == Register::RAX), remainder.1, format!("{} holds the quotient", remainder.0));
+        if !matches!(remainder.0, Object::Reg(reg) if reg == high) {
+            check_high_byte(&remainder, &(Object::Reg(high), remainder.1))?;
+            code.push_str(&format!("\tmov {dest}, {high}\n", dest = remainder.0));
+        }
+    }
+    Ok(code)
+}
+
+fn not_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have_no_phrases(), loc, "not instruction doesn't accept any phrases".to_string());
+    check_size(loc, &[&o.0])?;
+    check_destination(&o)?;
     Ok(format!("\tnot {dest}\n", dest = o.0))
 }
 
-fn call_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+// increment eax => inc eax, increment eax by 2 => add eax, 2
+fn inc_dec_instruction<'a>(verb: (Verb, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let (name, one, many) = match verb.0 {
+        Verb::Increment => ("increment", "inc", "add"),
+        _ => ("decrement", "dec", "sub"),
+    };
+    let by = pps.consume(Preposition::By);
+    check_if!(pps.have_no_phrases(), verb.1, format!("{} instruction accepts just 'by'", name));
+    check_size(verb.1, &[&o.0])?;
+    check_destination(&o)?;
+    match by {
+        Some(by) => {
+            check_operands(&o, &by, false)?;
+            Ok(format!("\t{verb} {dest}, {src}\n", verb = many, dest = o.0, src = by.0))
+        }
+        None => Ok(format!("\t{verb} {dest}\n", verb = one, dest = o.0)),
+    }
+}
+
+fn call_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have_no_phrases(), loc, "call instruction doesn't accept any phrases".to_string());
     Ok(format!("\tcall {dest}\n", dest = o.0))
 }
 
-fn negate_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+// the stack only takes 64-bit (or 16-bit) operands, so memory defaults to qword
+fn stack_operand<'a>(o: (Object<'a>, TokenLocation<'a>)) -> Result<Object<'a>, AsmError<'a>> {
+    match o {
+        (Object::Mem(mem), _) if mem.size().is_none() => Ok(Object::Mem(mem.sized(64))),
+        (obj @ (Object::Reg(_) | Object::Mem(_)), loc) => {
+            check_if!(matches!(obj.size(), Some(64 | 16)), loc, format!("{} can't be pushed or popped", obj));
+            Ok(obj)
+        }
+        (Object::Imm(i), loc) => {
+            check_if!(i32::try_from(i).is_ok(), loc, format!("{} doesn't fit in 32 bits", i));
+            Ok(Object::Imm(i))
+        }
+        (obj @ Object::Label(_), _) => Ok(obj),
+        (_, loc) => Err(AsmError::SyntaxError(loc, "expected a register, memory or immediate".to_string())),
+    }
+}
+
+fn push_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have_no_phrases(), loc, "push instruction doesn't accept any phrases".to_string());
+    Ok(format!("\tpush {src}\n", src = stack_operand(o)?))
+}
+
+fn pop_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have_no_phrases(), loc, "pop instruction doesn't accept any phrases".to_string());
+    check_if!(!matches!(o.0, Object::Imm(_)), o.1, "can't pop into an immediate".to_string());
+    Ok(format!("\tpop {dest}\n", dest = stack_operand(o)?))
+}
+
+// save pushes the registers in order and restore pops them in reverse,
+// so the same list can be written in both sentences
+fn list_instructions<'a>(v: (Verb, TokenLocation<'a>), objects: Vec<(Object<'a>, TokenLocation<'a>)>) -> Result<String, AsmError<'a>> {
+    let mut code = String::new();
+    match v.0 {
+        Verb::Save => {
+            for o in objects {
+                code.push_str(&push_instruction(v.1, o, PrepositionPhrases::default())?);
+            }
+        }
+        Verb::Restore => {
+            for o in objects.into_iter().rev() {
+                code.push_str(&pop_instruction(v.1, o, PrepositionPhrases::default())?);
+            }
+        }
+        _ => return Err(AsmError::SyntaxError(v.1, "something is wrong".to_string())),
+    }
+    Ok(code)
+}
+
+fn negate_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have_no_phrases(), loc, "negate instruction doesn't accept any phrases".to_string());
+    check_size(loc, &[&o.0])?;
+    check_destination(&o)?;
     Ok(format!("\tneg {dest}\n", dest = o.0))
 }
 
-fn mov_instruction<'a>(loc:TokenLocation<'a>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn mov_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     check_if!(pps.have(Preposition::To), loc, "move instruction requires 'to' phrase".to_string());
-    let to = pps.consume(Preposition::To).unwrap().0;
-    check_if!(pps.have_no_phrases(), loc, "move instruction accepts just 'to'".to_string());
-    Ok(format!("\tmov {dest}, {src}\n", dest = to, src = o.0))
+    let to = pps.consume(Preposition::To).unwrap();
+    let cc = condition_processer(&pps)?;
+    let extension = pps.consume(Preposition::As);
+    check_if!(pps.have_no_phrases(), loc, "move instruction accepts just 'to', 'if' and 'as'".to_string());
+    match extension {
+        Some(extension @ (Object::Keyword(Keyword::Signed | Keyword::ZeroExtened), _)) => return extending_mov_instruction(extension, to, o),
+        Some(float) => {
+            check_if!(cc.is_none(), loc, "floats can't be moved conditionally".to_string());
+            return float_mov_instruction(float_suffix(float)?, to, o);
+        }
+        None => (),
+    }
+    match cc {
+        // move rbx to rax if = => cmove rax, rbx
+        Some(cc) => {
+            check_if!(matches!(to.0, Object::Reg(_)) && matches!(to.0.size(), Some(16 | 32 | 64)), to.1, "conditional move needs a 16, 32 or 64-bit register destination".to_string());
+            check_if!(matches!(o.0, Object::Reg(_) | Object::Mem(_)), o.1, "conditional move can't take an immediate source".to_string());
+            check_operands(&to, &o, false)?;
+            check_size(loc, &[&to.0, &o.0])?;
+            Ok(format!("\tcmov{cc} {dest}, {src}\n", dest = to.0, src = o.0))
+        }
+        None => {
+            check_operands(&to, &o, true)?;
+            check_size(loc, &[&to.0, &o.0])?;
+            Ok(format!("\tmov {dest}, {src}\n", dest = to.0, src = o.0))
+        }
+    }
+}
+
+// load address of [rbp-16] to rdi => lea rdi, [rbp-16]
+// labels are addressed relative to rip so the output links as PIE
+fn lea_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(matches!(o.0, Object::Keyword(Keyword::Address)), o.1, "load instruction looks like 'load address of [rax] to rbx'".to_string());
+    check_if!(pps.have(Preposition::Of), loc, "load instruction requires 'of' phrase".to_string());
+    check_if!(pps.have(Preposition::To), loc, "load instruction requires 'to' phrase".to_string());
+    let (of, of_loc) = pps.consume(Preposition::Of).unwrap();
+    let (to, to_loc) = pps.consume(Preposition::To).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "load instruction accepts just 'of' and 'to'".to_string());
+    check_if!(matches!(to, Object::Reg(_)) && matches!(to.size(), Some(16 | 32 | 64)), to_loc, "load instruction needs a 16, 32 or 64-bit register destination".to_string());
+    match of {
+        Object::Mem(_) => Ok(format!("\tlea {dest}, {src}\n", dest = to, src = of)),
+        Object::Label(label) => Ok(format!("\tlea {dest}, [rip+{label}]\n", dest = to)),
+        _ => Err(AsmError::SyntaxError(of_loc, "only memory and labels have an address".to_string())),
+    }
+}
+
+// move al to eax as signed => movsx eax, al
+// move eax to rax as signed => movsxd rax, eax
+// move al to eax as zero-extended => movzx eax, al
+fn extending_mov_instruction<'a>(extension: (Object<'a>, TokenLocation<'a>), to: (Object<'a>, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>)) -> Result<String, AsmError<'a>> {
+    check_if!(matches!(to.0, Object::Reg(_)) && matches!(to.0.size(), Some(16 | 32 | 64)), to.1, "extending move needs a 16, 32 or 64-bit register destination".to_string());
+    check_if!(matches!(o.0, Object::Reg(_) | Object::Mem(_)), o.1, "extending move needs a register or memory source".to_string());
+    let (Some(dest_size), Some(src_size)) = (to.0.size(), o.0.size()) else {
+        return Err(AsmError::SyntaxError(o.1, "size of the source is unknown; use byte, word or dword".to_string()));
+    };
+    check_if!(src_size < dest_size, o.1, format!("{} must be narrower than {} to be extended", o.0, to.0));
+    check_high_byte(&to, &o)?;
+    let v = match (extension.0, src_size) {
+        (Object::Keyword(Keyword::Signed), 32) => "movsxd",
+        (Object::Keyword(Keyword::Signed), _) => "movsx",
+        // writing a 32-bit register clears the upper half: move ecx to rax as zero-extended => mov eax, ecx
+        (Object::Keyword(Keyword::ZeroExtened), 32) => {
+            let Object::Reg(dest) = to.0 else { unreachable!() };
+            return Ok(format!("\tmov {dest}, {src}\n", dest = dest.with_width(32).unwrap(), src = o.0));
+        }
+        _ => "movzx",
+    };
+    Ok(format!("\t{v} {dest}, {src}\n", dest = to.0, src = o.0))
 }
 
-fn jmp_if_processer<'a>(pps: &PrepositionPhrases<'a>) -> Result<&'a str, AsmError<'a>> {
-    match pps.consume(Preposition::If) {
-        Some((Object::Keyword(Keyword::NE), _)) => Ok("jne"),
-        Some((Object::Keyword(Keyword::E), _)) => Ok("je"),
-        Some((Object::Keyword(Keyword::G), _)) => Ok("jg"),
-        Some((Object::Keyword(Keyword::GE), _)) => Ok("jge"),
-        Some((Object::Keyword(Keyword::L), _)) => Ok("jl"),
-        Some((Object::Keyword(Keyword::LE), _)) => Ok("jle"),
-        None => Ok("jmp"),
-        Some((_, loc)) => Err(AsmError::SyntaxError::<'a>(loc, format!("as only takes key word")))
+// move xmm1 to xmm0 as double-precision-float => movsd xmm0, xmm1
+// move [rsi] to ymm0 as packed-doubles => vmovupd ymm0, [rsi]
+fn float_mov_instruction<'a>(suffix: &str, to: (Object<'a>, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>)) -> Result<String, AsmError<'a>> {
+    match to.0 {
+        Object::Mem(_) => check_float(suffix, &o, &to)?,
+        _ => check_float(suffix, &to, &o)?,
     }
+    // packed memory isn't necessarily 16 or 32-byte aligned, so use the unaligned moves
+    let v = match suffix {
+        "pd" => "movupd",
+        "ps" => "movups",
+        "sd" => "movsd",
+        _ => "movss",
+    };
+    let ymm = [&to.0, &o.0].iter().any(|o| matches!(o, Object::Reg(reg) if reg.class() == RegisterClass::Ymm));
+    Ok(format!("\t{vex}{v} {dest}, {src}\n", vex = if ymm { "v" } else { "" }, dest = to.0, src = o.0))
+}
+
+// convert eax to xmm0 as double-precision-float => cvtsi2sd xmm0, eax
+// convert xmm0 to rax => cvttsd2si rax, xmm0 (truncating, 'as' names the float when it's single)
+// convert xmm0 to xmm1 as single-precision-float => cvtsd2ss xmm1, xmm0
+fn convert_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have(Preposition::To), loc, "convert instruction requires 'to' phrase".to_string());
+    let to = pps.consume(Preposition::To).unwrap();
+    let suffix = as_processer(&pps)?;
+    check_if!(pps.have_no_phrases(), loc, "convert instruction accepts just 'to' and 'as'".to_string());
+    check_if!(!matches!(suffix, "pd" | "ps"), loc, "packed floats can't be converted".to_string());
+    let xmm = |o: &Object| matches!(o, Object::Reg(reg) if reg.class() == RegisterClass::Xmm);
+    let integer = |o: &(Object<'a>, TokenLocation<'a>)| -> Result<(), AsmError<'a>> {
+        check_if!(matches!(o.0, Object::Reg(_) | Object::Mem(_)) && matches!(o.0.size(), Some(32 | 64)), o.1, "conversion needs a 32 or 64-bit integer register or memory".to_string());
+        check_integer(o)
+    };
+    match (xmm(&to.0), xmm(&o.0)) {
+        (true, true) => {
+            let v = match suffix {
+                "sd" => "cvtss2sd",
+                "ss" => "cvtsd2ss",
+                _ => return Err(AsmError::SyntaxError(loc, "converting between floats needs 'as' with the target precision".to_string())),
+            };
+            Ok(format!("\t{v} {dest}, {src}\n", dest = to.0, src = o.0))
+        }
+        (true, false) => {
+            check_if!(!suffix.is_empty(), loc, "converting to a float needs 'as' with the target precision".to_string());
+            integer(&o)?;
+            Ok(format!("\tcvtsi2{suffix} {dest}, {src}\n", dest = to.0, src = o.0))
+        }
+        (false, _) => {
+            integer(&to)?;
+            let suffix = if suffix.is_empty() { "sd" } else { suffix };
+            let size = if suffix == "sd" { 64 } else { 32 };
+            let src = match o.0 {
+                Object::Mem(mem) if mem.size().is_none() => Object::Mem(mem.sized(size)),
+                src @ Object::Mem(_) => {
+                    check_if!(src.size() == Some(size), o.1, format!("{} doesn't hold a {}", src, float_name(suffix)));
+                    src
+                }
+                src if xmm(&src) => src,
+                _ => return Err(AsmError::SyntaxError(o.1, "conversion needs an xmm register, memory or integer source".to_string())),
+            };
+            Ok(format!("\tcvtt{suffix}2si {dest}, {src}\n", dest = to.0))
+        }
+    }
+}
+
+// exchange rax with [rbx] => xchg rax, [rbx]
+fn xchg_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have(Preposition::With), loc, "exchange instruction requires 'with' phrase".to_string());
+    let with = pps.consume(Preposition::With).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "exchange instruction accepts just 'with'".to_string());
+    check_if!(matches!(with.0, Object::Reg(_) | Object::Mem(_)), with.1, "exchange instruction needs a register or memory".to_string());
+    check_operands(&o, &with, false)?;
+    check_size(loc, &[&o.0, &with.0])?;
+    Ok(format!("\txchg {dest}, {src}\n", dest = o.0, src = with.0))
+}
+
+// compare-and-exchange [rdi] with rsi => cmpxchg [rdi], rsi
+// [rdi] is compared with rax and replaced by rsi if they are equal
+fn cmpxchg_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have(Preposition::With), loc, "compare-and-exchange instruction requires 'with' phrase".to_string());
+    let with = pps.consume(Preposition::With).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "compare-and-exchange instruction accepts just 'with'".to_string());
+    check_if!(matches!(with.0, Object::Reg(_)), with.1, "compare-and-exchange instruction needs a register source".to_string());
+    check_operands(&o, &with, false)?;
+    Ok(format!("\tcmpxchg {dest}, {src}\n", dest = o.0, src = with.0))
+}
+
+// fetch-and-add rax to [rdi] => xadd [rdi], rax
+// rax gets the old value of [rdi]
+fn xadd_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have(Preposition::To), loc, "fetch-and-add instruction requires 'to' phrase".to_string());
+    let to = pps.consume(Preposition::To).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "fetch-and-add instruction accepts just 'to'".to_string());
+    check_if!(matches!(o.0, Object::Reg(_)), o.1, "fetch-and-add instruction needs a register source".to_string());
+    check_operands(&to, &o, false)?;
+    Ok(format!("\txadd {dest}, {src}\n", dest = to.0, src = o.0))
+}
+
+// atomically add 1 to qword [rax] => lock add QWORD PTR [rax], 1
+// lock is only legal on read-modify-write instructions with a memory destination
+fn atomic_instruction<'a>(atomic: (Sentence<'a>, TokenLocation<'a>)) -> Result<String, AsmError<'a>> {
+    let (sentence, loc) = atomic;
+    let Sentence::Sentence { verb, object: Some(obj), prepositional_phrases: pps } = sentence else {
+        return Err(AsmError::SyntaxError(loc, "only a sentence with an object can be atomic".to_string()));
+    };
+    let memory = |o: &Object| matches!(o, Object::Mem(_));
+    let lockable = match verb.0 {
+        Verb::Add | Verb::FetchAndAdd => pps.matches(Preposition::To, memory),
+        Verb::Substract => pps.matches(Preposition::From, memory),
+        Verb::And | Verb::Or | Verb::Xor | Verb::Not | Verb::Negate | Verb::Increment | Verb::Decrement | Verb::CompareAndExchange => memory(&obj.0),
+        Verb::BitTestAndSet | Verb::BitTestAndReset | Verb::BitTestAndComplement => pps.matches(Preposition::Of, memory),
+        Verb::Exchange => memory(&obj.0) || pps.matches(Preposition::With, memory),
+        _ => return Err(AsmError::SyntaxError(verb.1, "this instruction can't be atomic".to_string())),
+    };
+    check_if!(lockable, loc, "atomic instructions need a memory destination".to_string());
+    let code = instruction(Sentence::Sentence { verb, object: Some(obj), prepositional_phrases: pps })?;
+    Ok(format!("\tlock {}", code.trim_start_matches('\t')))
+}
+
+// b, w, d or q from the unit of a string instruction: copy rcx quadwords => movsq
+fn string_unit<'a>(pps: &PrepositionPhrases<'a>) -> Result<Option<usize>, AsmError<'a>> {
+    match pps.consume(Preposition::As) {
+        Some((Object::Keyword(key), _)) if key.size().is_some() => Ok(key.size()),
+        Some((_, loc)) => Err(AsmError::SyntaxError(loc, "expected bytes, words, dwords or quadwords".to_string())),
+        None => Ok(None),
+    }
+}
+
+fn string_suffix(size: usize) -> &'static str {
+    match size {
+        8 => "b",
+        16 => "w",
+        32 => "d",
+        _ => "q",
+    }
+}
+
+fn uses(o: &Object, reg: Register) -> bool {
+    match o {
+        Object::Reg(r) => r.parent() == reg.parent(),
+        Object::Mem(mem) => mem.uses(reg),
+        _ => false,
+    }
+}
+
+// moves the operands of a string instruction into the registers it works on,
+// refusing an order where a move reads a register an earlier move overwrote
+fn string_setup<'a>(moves: &[(Register, &(Object<'a>, TokenLocation<'a>))]) -> Result<String, AsmError<'a>> {
+    let mut code = String::new();
+    let mut written: Vec<Register> = Vec::new();
+    for (target, src) in moves {
+        if let Some(reg) = written.iter().find(|reg| uses(&src.0, **reg)) {
+            return Err(AsmError::SyntaxError(src.1, format!("{} is overwritten before this operand is read; use another register", reg)));
+        }
+        match &src.0 {
+            Object::Reg(reg) if reg == target => continue,
+            Object::Label(label) if target.width() == 64 => code.push_str(&format!("\tlea {target}, [rip+{label}]\n")),
+            _ => {
+                check_operands(&(Object::Reg(*target), src.1), src, true)?;
+                check_size(src.1, &[&Object::Reg(*target), &src.0])?;
+                code.push_str(&format!("\tmov {target}, {src}\n", src = src.0));
+            }
+        }
+        written.push(target.parent());
+    }
+    Ok(code)
+}
+
+// copy rcx bytes from rsi to rdi => rep movsb
+fn copy_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let size = string_unit(&pps)?;
+    check_if!(pps.have(Preposition::From), loc, "copy instruction requires 'from' phrase".to_string());
+    check_if!(pps.have(Preposition::To), loc, "copy instruction requires 'to' phrase".to_string());
+    let from = pps.consume(Preposition::From).unwrap();
+    let to = pps.consume(Preposition::To).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "copy instruction accepts just 'from' and 'to'".to_string());
+    let Some(size) = size else {
+        return Err(AsmError::SyntaxError(o.1, "copy instruction needs a unit: copy rcx bytes".to_string()));
+    };
+    let setup = string_setup(&[(Register::RCX, &o), (Register::RSI, &from), (Register::RDI, &to)])?;
+    Ok(format!("{setup}\trep movs{}\n", string_suffix(size)))
+}
+
+// fill rcx quadwords at rdi with rax => rep stosq
+fn fill_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let size = string_unit(&pps)?;
+    check_if!(pps.have(Preposition::At), loc, "fill instruction requires 'at' phrase".to_string());
+    check_if!(pps.have(Preposition::With), loc, "fill instruction requires 'with' phrase".to_string());
+    let at = pps.consume(Preposition::At).unwrap();
+    let with = pps.consume(Preposition::With).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "fill instruction accepts just 'at' and 'with'".to_string());
+    let Some(size) = size else {
+        return Err(AsmError::SyntaxError(o.1, "fill instruction needs a unit: fill rcx bytes".to_string()));
+    };
+    let value = Register::RAX.with_width(size).unwrap();
+    let setup = string_setup(&[(Register::RCX, &o), (Register::RDI, &at), (value, &with)])?;
+    Ok(format!("{setup}\trep stos{}\n", string_suffix(size)))
+}
+
+// scan for al in rdi => repne scasb, with rcx = -1 when no count is given
+fn scan_instruction<'a>(loc:TokenLocation<'a>, o: Option<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    let unit = string_unit(&pps)?;
+    check_if!(pps.have(Preposition::For), loc, "scan instruction requires 'for' phrase".to_string());
+    check_if!(pps.have(Preposition::In), loc, "scan instruction requires 'in' phrase".to_string());
+    let value = pps.consume(Preposition::For).unwrap();
+    let within = pps.consume(Preposition::In).unwrap();
+    check_if!(pps.have_no_phrases(), loc, "scan instruction accepts just 'for' and 'in'".to_string());
+    let general = matches!(value.0, Object::Imm(_) | Object::Mem(_)) || matches!(value.0, Object::Reg(reg) if reg.class() == RegisterClass::GeneralPurpose);
+    check_if!(general, value.1, "scan instruction looks for a general purpose register, memory or immediate".to_string());
+    let size = unit.or(value.0.size()).unwrap_or(8);
+    let Some(accumulator) = Register::RAX.with_width(size) else {
+        return Err(AsmError::SyntaxError(value.1, "scan instruction looks for bytes, words, dwords or quadwords".to_string()));
+    };
+    let count = o.unwrap_or((Object::Imm(-1), loc));
+    let setup = string_setup(&[(Register::RCX, &count), (Register::RDI, &within), (accumulator, &value)])?;
+    Ok(format!("{setup}\trepne scas{}\n", string_suffix(size)))
+}
+
+// set al if < => setl al
+fn set_instruction<'a>(loc:TokenLocation<'a>, o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    check_if!(pps.have(Preposition::If), loc, "set instruction requires 'if' phrase".to_string());
+    let cc = condition_processer(&pps)?.unwrap();
+    check_if!(pps.have_no_phrases(), loc, "set instruction accepts just 'if'".to_string());
+    let dest = match o.0 {
+        Object::Mem(mem) if mem.size().is_none() => Object::Mem(mem.sized(8)),
+        dest => dest,
+    };
+    check_if!(matches!(dest, Object::Reg(_) | Object::Mem(_)) && dest.size() == Some(8), o.1, "set instruction needs a byte register or memory".to_string());
+    Ok(format!("\tset{cc} {dest}\n"))
+}
+
+// condition codes for every sentence with an 'if' phrase.
+// `as unsigned` turns the ordering comparisons into below/above.
+fn condition_processer<'a>(pps: &PrepositionPhrases<'a>) -> Result<Option<&'static str>, AsmError<'a>> {
+    let Some((cond, loc)) = pps.consume(Preposition::If) else {
+        return Ok(None);
+    };
+    let unsigned = match pps.consume(Preposition::As) {
+        Some((Object::Keyword(Keyword::Unsigned), _)) => true,
+        Some((Object::Keyword(Keyword::Signed), _)) | None => false,
+        Some((_, loc)) => return Err(AsmError::SyntaxError(loc, "condition can only be signed or unsigned".to_string())),
+    };
+    let Object::Keyword(cond) = cond else {
+        return Err(AsmError::SyntaxError(loc, "if only takes a condition".to_string()));
+    };
+    let cc = match (cond, unsigned) {
+        (Keyword::E, _) => "e",
+        (Keyword::NE, _) => "ne",
+        (Keyword::L, false) => "l",
+        (Keyword::LE, false) => "le",
+        (Keyword::G, false) => "g",
+        (Keyword::GE, false) => "ge",
+        (Keyword::L | Keyword::B, true) | (Keyword::B, false) => "b",
+        (Keyword::LE | Keyword::BE, true) | (Keyword::BE, false) => "be",
+        (Keyword::G | Keyword::A, true) | (Keyword::A, false) => "a",
+        (Keyword::GE | Keyword::AE, true) | (Keyword::AE, false) => "ae",
+        (_, true) => return Err(AsmError::SyntaxError(loc, "only comparisons can be unsigned".to_string())),
+        (Keyword::Zero, _) => "z",
+        (Keyword::NotZero, _) => "nz",
+        (Keyword::Carry, _) => "c",
+        (Keyword::NotCarry, _) => "nc",
+        (Keyword::Overflow, _) => "o",
+        (Keyword::NotOverflow, _) => "no",
+        (Keyword::Sign, _) => "s",
+        (Keyword::NotSign, _) => "ns",
+        (Keyword::Parity, _) => "p",
+        (Keyword::NotParity, _) => "np",
+        _ => return Err(AsmError::SyntaxError(loc, "unknown condition".to_string())),
+    };
+    Ok(Some(cc))
+}
+
+fn jmp_if_processer<'a>(pps: &PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+    Ok(match condition_processer(pps)? {
+        Some(cc) => format!("j{}", cc),
+        None => "jmp".to_string(),
+    })
 }
 
-fn vi_instructions<'a>(v: Box<(Verb, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn vi_instructions<'a>(v: (Verb, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     match v.0 {
-        Verb::Return => Ok(format!("\tret\n")),
-        Verb::Leave => Ok(format!("\tleave\n")),
-        Verb::NoOperation => Ok(format!("\tnop\n")),
-        Verb::SystemCall => Ok(format!("\tsyscall\n")),
-        Verb::Halt => Ok(format!("\thlt\n")),
+        Verb::Return => Ok("\tret\n".to_string()),
+        Verb::Leave => Ok("\tleave\n".to_string()),
+        Verb::NoOperation => Ok("\tnop\n".to_string()),
+        Verb::SystemCall => Ok("\tsyscall\n".to_string()),
+        Verb::Halt => Ok("\thlt\n".to_string()),
         Verb::Jump => {
+            check_if!(pps.have(Preposition::To), v.1, "jump instruction requires 'to' phrase".to_string());
             let to = pps.consume(Preposition::To).unwrap().0;
             let verb = jmp_if_processer(&pps)?;
-            check_if!(pps.have_no_phrases(), v.1, "jump instruction accepts just 'to'".to_string());
+            check_if!(pps.have_no_phrases(), v.1, "jump instruction accepts just 'to', 'if' and 'as'".to_string());
             Ok(format!("\t{verb} {dest}\n", dest = to))
         }
-        _ => Err(AsmError::SyntaxError(v.1, format!("something is wrong"))),
+        _ => Err(AsmError::SyntaxError(v.1, "something is wrong".to_string())),
     }
 }
 
-fn logical_binary_instruction<'a>(verb:  Box<(Verb, TokenLocation<'a>)>, o: Box<(Object<'a>, TokenLocation<'a>)>, pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
+fn logical_binary_instruction<'a>(verb: (Verb, TokenLocation<'a>), o: (Object<'a>, TokenLocation<'a>), pps: PrepositionPhrases<'a>) -> Result<String, AsmError<'a>> {
     let v = match verb.0 {
         Verb::And => Some("and"),
         Verb::Or => Some("or"),
@@ -163,7 +957,9 @@ fn logical_binary_instruction<'a>(verb:  Box<(Verb, TokenLocation<'a>)>, o: Box<
         _ => None
     }.unwrap();
     check_if!(pps.have(Preposition::With), verb.1, "logical opeation instructions require 'With' phrase".to_string());
-    let by = pps.consume(Preposition::With).unwrap().0;
+    let by = pps.consume(Preposition::With).unwrap();
     check_if!(pps.have_no_phrases(), verb.1, "logical opeation instructions accept just 'with'".to_string());
-    Ok(format!("\t{verb} {dest}, {src}\n", verb = v, dest = o.0, src = by))
+    check_operands(&o, &by, false)?;
+    check_size(verb.1, &[&o.0, &by.0])?;
+    Ok(format!("\t{verb} {dest}, {src}\n", verb = v, dest = o.0, src = by.0))
 }
\ No newline at end of file
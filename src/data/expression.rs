@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+
+use super::Symbols;
+
+// 42, -42, 0xff, 0b1010, 0o755, 1_000_000 and 'A'.
+// Ok(None) means the token isn't a number at all, so it may still be a label.
+pub(crate) fn parse_number(token: &str) -> Result<Option<i128>, String> {
+    let (negative, digits) = match token.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+    let value = if let Some(literal) = digits.strip_prefix('\'').and_then(|rest| rest.strip_suffix('\'')) {
+        let c = match literal {
+            "\\n" => '\n',
+            "\\t" => '\t',
+            "\\r" => '\r',
+            "\\0" => '\0',
+            "\\\\" => '\\',
+            "\\'" => '\'',
+            _ => {
+                let mut chars = literal.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(format!("invalid character literal {}", token)),
+                }
+            }
+        };
+        i128::from(u32::from(c))
+    } else if digits.starts_with(|c: char| c.is_ascii_digit()) {
+        let (radix, body) = match digits.get(..2) {
+            Some("0x" | "0X") => (16, &digits[2..]),
+            Some("0b" | "0B") => (2, &digits[2..]),
+            Some("0o" | "0O") => (8, &digits[2..]),
+            _ => (10, digits),
+        };
+        let body = body.replace('_', "");
+        if body.is_empty() || !body.chars().all(|c| c.is_digit(radix)) {
+            return Err(format!("invalid number {}", token));
+        }
+        u128::from_str_radix(&body, radix)
+            .ok()
+            .and_then(|v| i128::try_from(v).ok())
+            .ok_or(format!("{} doesn't fit in 64 bits", token))?
+    } else {
+        return Ok(None);
+    };
+    let value = if negative { -value } else { value };
+    if value < i128::from(i64::MIN) || value > i128::from(u64::MAX) {
+        return Err(format!("{} doesn't fit in 64 bits", token));
+    }
+    Ok(Some(value))
+}
+
+// constant expressions: (4 * 8 + 1), 8*3, end - start.
+// Ok(None) means the expression refers to labels and is left to the assembler.
+pub(crate) fn evaluate(expr: &str, symbols: &Symbols) -> Result<Option<i128>, String> {
+    let mut parser = Parser { tokens: split(expr)?, position: 0, symbolic: false, symbols };
+    let value = parser.expression()?;
+    if let Some(token) = parser.tokens.get(parser.position) {
+        return Err(format!("unexpected '{}' in expression", token));
+    }
+    if parser.symbolic {
+        return Ok(None);
+    }
+    if value < i128::from(i64::MIN) || value > i128::from(u64::MAX) {
+        return Err(format!("{} doesn't fit in 64 bits", expr));
+    }
+    Ok(Some(value))
+}
+
+// constants inside expressions left to the assembler: (table + SLOT) => ( table + 8 )
+pub(crate) fn substitute<'e>(expr: &'e str, symbols: &Symbols) -> Result<Cow<'e, str>, String> {
+    let tokens = split(expr)?;
+    if !tokens.iter().any(|token| symbols.constant(token).is_some()) {
+        return Ok(Cow::Borrowed(expr));
+    }
+    let tokens: Vec<String> = tokens
+        .into_iter()
+        .map(|token| match symbols.constant(token) {
+            Some(value) if value < 0 => format!("({})", value),
+            Some(value) => value.to_string(),
+            None => token.to_string(),
+        })
+        .collect();
+    Ok(Cow::Owned(tokens.join(" ")))
+}
+
+// labels an expression refers to: end - start => end, start
+pub(crate) fn names(expr: &str) -> Vec<&str> {
+    split(expr)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|token| token.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '.'))
+        .collect()
+}
+
+pub(crate) fn is_operator(token: &str) -> bool {
+    matches!(token, "+" | "-" | "*" | "/" | "%")
+}
+
+// operators and parentheses are tokens even without spaces around them
+fn split(expr: &str) -> Result<Vec<&str>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+    while !rest.is_empty() {
+        let len = if rest.starts_with(|c| "+-*/%()".contains(c)) {
+            1
+        } else if rest.starts_with('\'') {
+            // a character literal may be an operator or a space: '+', ' '
+            let mut escaped = false;
+            rest.char_indices()
+                .skip(1)
+                .find(|(_, c)| {
+                    let end = *c == '\'' && !escaped;
+                    escaped = *c == '\\' && !escaped;
+                    end
+                })
+                .map(|(i, _)| i + 1)
+                .ok_or(format!("unterminated character literal in {}", expr))?
+        } else {
+            rest.find(|c: char| c.is_whitespace() || "+-*/%()".contains(c)).unwrap_or(rest.len())
+        };
+        tokens.push(&rest[..len]);
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'s> {
+    tokens: Vec<&'s str>,
+    position: usize,
+    // a label was seen, so the value is only known at link time
+    symbolic: bool,
+    symbols: &'s Symbols,
+}
+
+impl<'s> Parser<'s> {
+    fn peek(&self) -> Option<&'s str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Result<&'s str, String> {
+        let token = self.peek().ok_or("expression ends unexpectedly".to_string())?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    // symbolic expressions are checked for syntax only
+    fn apply(&self, op: &str, lhs: i128, rhs: i128) -> Result<i128, String> {
+        let value = match op {
+            "+" => lhs.checked_add(rhs),
+            "-" => lhs.checked_sub(rhs),
+            "*" => lhs.checked_mul(rhs),
+            _ if rhs == 0 && !self.symbolic => return Err("division by zero in expression".to_string()),
+            "/" => lhs.checked_div(rhs),
+            _ => lhs.checked_rem(rhs),
+        };
+        match value {
+            Some(value) if value.unsigned_abs() <= u128::from(u64::MAX) => Ok(value),
+            _ if self.symbolic => Ok(0),
+            _ => Err("expression overflows".to_string()),
+        }
+    }
+
+    fn expression(&mut self) -> Result<i128, String> {
+        let mut value = self.term()?;
+        while let Some(op @ ("+" | "-")) = self.peek() {
+            self.position += 1;
+            let rhs = self.term()?;
+            value = self.apply(op, value, rhs)?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<i128, String> {
+        let mut value = self.unary()?;
+        while let Some(op @ ("*" | "/" | "%")) = self.peek() {
+            self.position += 1;
+            let rhs = self.unary()?;
+            value = self.apply(op, value, rhs)?;
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<i128, String> {
+        if self.peek() == Some("-") {
+            self.position += 1;
+            return Ok(-self.unary()?);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<i128, String> {
+        let token = self.next()?;
+        if token == "(" {
+            let value = self.expression()?;
+            if self.next()? != ")" {
+                return Err("missing ')' in expression".to_string());
+            }
+            return Ok(value);
+        }
+        if let Some(num) = parse_number(token)?.or(self.symbols.constant(token)) {
+            return Ok(num);
+        }
+        if self.symbols.register(token).is_some() {
+            return Err(format!("{} can't be used in a constant expression", token));
+        }
+        if !token.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '.') {
+            return Err(format!("unexpected '{}' in expression", token));
+        }
+        self.symbolic = true;
+        Ok(0)
+    }
+}
@@ -1,13 +1,22 @@
+pub mod assembly;
 pub mod codegen;
+pub mod expression;
+pub mod program;
+pub mod register;
 pub mod sentence;
+pub mod symbols;
 pub mod token;
 use core::fmt;
 
+pub use assembly::Assembly;
 pub use codegen::codegen;
+pub use program::Program;
+pub use register::{Register, RegisterClass};
 pub use sentence::Sentence;
+pub use symbols::Symbols;
 pub use token::{Token, TokenLocation};
 
-pub(crate) use sentence::{Object, Preposition, PrepositionPhrases, Verb};
+pub(crate) use sentence::{Data, Object, Preposition, PrepositionPhrases, Verb};
 
 #[derive(Debug, Clone)]
 pub enum AsmError<'a> {
@@ -24,9 +33,3 @@ impl<'a> fmt::Display for AsmError<'a> {
         
     }
 }
-
-pub fn compile<'a>(token: &'a Box<Token<'a>>, code: & mut String) -> Result<(), AsmError<'a>> {
-    let sentence = Sentence::parse(&token)?;
-        code.push_str(&codegen(sentence)?);
-        Ok(())
-}
@@ -0,0 +1,66 @@
+use super::{codegen, AsmError, Assembly, Sentence, Symbols, Token, TokenLocation};
+
+// a whole file: every sentence is parsed before any code is generated,
+// so labels and constants are known regardless of where they are defined
+pub struct Program<'a> {
+    sentences: Vec<Sentence<'a>>,
+    symbols: &'a Symbols,
+}
+
+impl<'a> Program<'a> {
+    pub fn parse(file: &'a str, source: &'a str, symbols: &'a Symbols) -> Result<Self, Vec<AsmError<'a>>> {
+        let mut sentences = Vec::new();
+        let mut errors = Vec::new();
+        for (ln, line) in source.lines().enumerate() {
+            let token = Token::tokenize(line, TokenLocation::new(file, ln, 0), symbols);
+            match Sentence::parse(&token) {
+                Ok(sentence) => sentences.push(sentence),
+                Err(e) => errors.push(e),
+            }
+        }
+        let program = Self { sentences, symbols };
+        errors.extend(program.undefined_labels());
+        if errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(errors)
+        }
+    }
+
+    // labels must be defined in the file or imported
+    fn undefined_labels(&self) -> Vec<AsmError<'a>> {
+        let imports: Vec<&str> = self
+            .sentences
+            .iter()
+            .filter_map(|s| match s {
+                Sentence::Import(label) => Some(label.0),
+                _ => None,
+            })
+            .collect();
+        self.sentences
+            .iter()
+            .flat_map(|s| s.references())
+            .filter(|(name, _)| !self.symbols.is_label(name) && !imports.contains(&name.as_str()))
+            .filter_map(|(name, loc)| match self.symbols.constant_line(&name) {
+                // constants defined earlier were already replaced by their values
+                Some(line) if line < loc.line() => None,
+                Some(_) => Some(AsmError::SyntaxError(loc, format!("constant {} is used before it is defined", name))),
+                None => Some(AsmError::SyntaxError(loc, format!("label {} is never defined; import it if it is external", name))),
+            })
+            .collect()
+    }
+
+    pub fn codegen(self) -> Result<Assembly, Vec<AsmError<'a>>> {
+        let mut code = Assembly::default();
+        let errors: Vec<AsmError<'a>> = self
+            .sentences
+            .into_iter()
+            .filter_map(|s| codegen(s, &mut code).err())
+            .collect();
+        if errors.is_empty() {
+            Ok(code)
+        } else {
+            Err(errors)
+        }
+    }
+}
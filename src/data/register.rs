@@ -0,0 +1,226 @@
+use std::fmt;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    // general purpose regiser
+    AL,
+    BL,
+    CL,
+    DL,
+    DIL,
+    SIL,
+    BPL,
+    SPL,
+    R8B,
+    R9B,
+    R10B,
+    R11B,
+    R12B,
+    R13B,
+    R14B,
+    R15B, // byte
+    AH,
+    BH,
+    CH,
+    DH, // high byte
+    AX,
+    BX,
+    CX,
+    DX,
+    DI,
+    SI,
+    BP,
+    SP,
+    R8W,
+    R9W,
+    R10W,
+    R11W,
+    R12W,
+    R13W,
+    R14W,
+    R15W, // word
+    EAX,
+    EBX,
+    ECX,
+    EDX,
+    EDI,
+    ESI,
+    EBP,
+    ESP,
+    R8D,
+    R9D,
+    R10D,
+    R11D,
+    R12D,
+    R13D,
+    R14D,
+    R15D, // doubleword
+    RAX,
+    RBX,
+    RCX,
+    RDX,
+    RDI,
+    RSI,
+    RBP,
+    RSP,
+    R8,
+    R9,
+    R10,
+    R11,
+    R12,
+    R13,
+    R14,
+    R15, //  quadword
+
+    XMM0,
+    XMM1,
+    XMM2,
+    XMM3,
+    XMM4,
+    XMM5,
+    XMM6,
+    XMM7,
+    XMM8,
+    XMM9,
+    XMM10,
+    XMM11,
+    XMM12,
+    XMM13,
+    XMM14,
+    XMM15,
+
+    YMM0,
+    YMM1,
+    YMM2,
+    YMM3,
+    YMM4,
+    YMM5,
+    YMM6,
+    YMM7,
+    YMM8,
+    YMM9,
+    YMM10,
+    YMM11,
+    YMM12,
+    YMM13,
+    YMM14,
+    YMM15,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterClass {
+    GeneralPurpose,
+    Xmm,
+    Ymm,
+}
+
+use Register::*;
+
+// one row per 64-bit register: byte, word, doubleword, quadword
+const GENERAL_PURPOSE: [[Register; 4]; 16] = [
+    [AL, AX, EAX, RAX],
+    [BL, BX, EBX, RBX],
+    [CL, CX, ECX, RCX],
+    [DL, DX, EDX, RDX],
+    [DIL, DI, EDI, RDI],
+    [SIL, SI, ESI, RSI],
+    [BPL, BP, EBP, RBP],
+    [SPL, SP, ESP, RSP],
+    [R8B, R8W, R8D, R8],
+    [R9B, R9W, R9D, R9],
+    [R10B, R10W, R10D, R10],
+    [R11B, R11W, R11D, R11],
+    [R12B, R12W, R12D, R12],
+    [R13B, R13W, R13D, R13],
+    [R14B, R14W, R14D, R14],
+    [R15B, R15W, R15D, R15],
+];
+
+const HIGH_BYTE: [(Register, Register); 4] = [(AH, RAX), (BH, RBX), (CH, RCX), (DH, RDX)];
+
+const XMM: [Register; 16] = [
+    XMM0, XMM1, XMM2, XMM3, XMM4, XMM5, XMM6, XMM7, XMM8, XMM9, XMM10, XMM11, XMM12, XMM13, XMM14, XMM15,
+];
+
+const YMM: [Register; 16] = [
+    YMM0, YMM1, YMM2, YMM3, YMM4, YMM5, YMM6, YMM7, YMM8, YMM9, YMM10, YMM11, YMM12, YMM13, YMM14, YMM15,
+];
+
+impl Register {
+    fn all() -> impl Iterator<Item = Register> {
+        GENERAL_PURPOSE
+            .iter()
+            .flatten()
+            .copied()
+            .chain(HIGH_BYTE.iter().map(|(reg, _)| *reg))
+            .chain(XMM)
+            .chain(YMM)
+    }
+
+    pub(crate) fn parse(token: &str) -> Option<Self> {
+        Self::all().find(|reg| reg.to_string() == token)
+    }
+
+    // (row, column) in GENERAL_PURPOSE
+    fn position(&self) -> Option<(usize, usize)> {
+        GENERAL_PURPOSE
+            .iter()
+            .enumerate()
+            .find_map(|(row, regs)| regs.iter().position(|reg| reg == self).map(|column| (row, column)))
+    }
+
+    pub(crate) fn class(&self) -> RegisterClass {
+        if XMM.contains(self) {
+            RegisterClass::Xmm
+        } else if YMM.contains(self) {
+            RegisterClass::Ymm
+        } else {
+            RegisterClass::GeneralPurpose
+        }
+    }
+
+    // in bits
+    pub(crate) fn width(&self) -> usize {
+        match (self.class(), self.position()) {
+            (RegisterClass::Xmm, _) => 128,
+            (RegisterClass::Ymm, _) => 256,
+            (_, Some((_, column))) => 8 << column,
+            _ => 8,
+        }
+    }
+
+    // the 64-bit register this one is part of: eax => rax, ah => rax
+    pub(crate) fn parent(&self) -> Register {
+        match (self.position(), HIGH_BYTE.iter().find(|(reg, _)| reg == self)) {
+            (Some((row, _)), _) => GENERAL_PURPOSE[row][3],
+            (_, Some((_, parent))) => *parent,
+            _ => *self,
+        }
+    }
+
+    // rax.with_width(8) == Some(al)
+    pub(crate) fn with_width(&self, width: usize) -> Option<Register> {
+        let (row, _) = self.parent().position()?;
+        GENERAL_PURPOSE[row].iter().find(|reg| reg.width() == width).copied()
+    }
+
+    pub(crate) fn is_high_byte(&self) -> bool {
+        HIGH_BYTE.iter().any(|(reg, _)| reg == self)
+    }
+
+    // r8-r15, xmm8-xmm15, ymm8-ymm15 and the low bytes of rsp, rbp, rsi and rdi can only be
+    // encoded with a REX (or VEX) prefix, which in turn makes ah, bh, ch and dh unreachable
+    pub(crate) fn needs_rex(&self) -> bool {
+        match self.position() {
+            Some((row, column)) => row >= 8 || (row >= 4 && column == 0),
+            None => XMM[8..].contains(self) || YMM[8..].contains(self),
+        }
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format!("{:?}", self).to_lowercase())
+    }
+}
@@ -1,11 +1,13 @@
-use super::{AsmError, Token, TokenLocation};
+use super::expression::{evaluate, is_operator, names, parse_number, substitute};
+use super::{AsmError, Register, RegisterClass, Symbols, Token, TokenLocation};
 
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
 
 type Label<'a> = &'a str;
-# [derive(Debug)]
+#[derive(Debug)]
 pub(crate) enum Verb {
     Add,
     Substract,
@@ -20,9 +22,39 @@ pub(crate) enum Verb {
     Negate,
     ShiftRight,
     ShiftLeft,
+    RotateRight,
+    RotateLeft,
     Call,
     Compare,
+    Test,
+    Check,
+    BitTest,
+    BitTestAndSet,
+    BitTestAndReset,
+    BitTestAndComplement,
+    Increment,
+    Decrement,
+    Set,
+    Exchange,
+    CompareAndExchange,
+    FetchAndAdd,
+    Load,
+    Convert,
+    Copy,
+    Fill,
+    Scan,
+    Push,
+    Pop,
+    Save,
+    Restore,
+
+    // data definitions
+    Define,
+    Reserve,
 
+    // symbol visibility
+    Export,
+    Import,
 
     // intransitive verbs
     Return,
@@ -32,99 +64,26 @@ pub(crate) enum Verb {
     Halt,
 }
 
+// base + index * scale + displacement
 #[derive(Debug)]
-pub(crate) struct Memory {
-    base: Register, // Option<Register>,
-                    // Index: Option<Register>,
-                    // Scale: Option<u8>
+pub(crate) struct Memory<'a> {
+    base: Option<Register>,
+    index: Option<Register>,
+    scale: u8,
+    displacement: i64,
+    // a label in the displacement, addressed relative to rip
+    label: Option<&'a str>,
+    size: Option<usize>,
 }
 
 #[derive(Debug)]
 pub(crate) enum Object<'a> {
     Reg(Register),
-    Imm(i64),
-    Mem(Memory),
-    Label(Label<'a>),
+    Imm(i128), // anything from i64::MIN to u64::MAX
+    Mem(Memory<'a>),
+    Label(Cow<'a, str>),
     Keyword(Keyword)
 }
-#[derive(Debug)]
-pub enum Register {
-    // general purpose regiser
-    AL,
-    BL,
-    CL,
-    DL,
-    DIL,
-    SIL,
-    BPL,
-    SPL,
-    R8B,
-    R9B,
-    R10B,
-    R11B,
-    R12B,
-    R13B,
-    R14B,
-    R15B, // byte
-    AX,
-    BX,
-    CX,
-    DX,
-    DI,
-    SI,
-    BP,
-    SP,
-    R8W,
-    R9W,
-    R10W,
-    R11W,
-    R12W,
-    R13W,
-    R14W,
-    R15W, // word
-    EAX,
-    EBX,
-    ECX,
-    EDX,
-    EDI,
-    ESI,
-    EBP,
-    ESP,
-    R8D,
-    R9D,
-    R10D,
-    R11D,
-    R12D,
-    R13D,
-    R14D,
-    R15D, // doubleword
-    RAX,
-    RBX,
-    RCX,
-    RDX,
-    RDI,
-    RSI,
-    RBP,
-    RSP,
-    R8,
-    R9,
-    R10,
-    R11,
-    R12,
-    R13,
-    R14,
-    R15, //  quadword
-
-    XMM0,
-    XMM1,
-    XMM2,
-    XMM3,
-    XMM4,
-    XMM5,
-    XMM6,
-    XMM7,
-}
-
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub(crate) enum Preposition {
     To,
@@ -133,22 +92,53 @@ pub(crate) enum Preposition {
     As,
     With,
     If, // unofficial
+    In,
+    Of,
+    At,
+    For,
+    Keep, // keep remainder in rdx
 }
 
 #[derive(Debug)]
 pub(crate) enum Keyword {
     DoublePrecisionFloat,
     SinglePrecisionFloat,
+    PackedDoubles,
+    PackedSingles,
     Signed,
+    Unsigned,
     ZeroExtened,
+    String,
+    Bits,
+    Address,
+    Remainder,
+    Byte,
+    Word,
+    DoubleWord,
+    QuadWord,
     E,
     G,
     L,
     NE,
     GE,
     LE,
+    B,
+    BE,
+    A,
+    AE,
+    Zero,
+    NotZero,
+    Carry,
+    NotCarry,
+    Overflow,
+    NotOverflow,
+    Sign,
+    NotSign,
+    Parity,
+    NotParity,
 }
 
+#[allow(clippy::enum_variant_names)]
 pub enum Sentence<'a> {
     Sentence {
         verb: Box<(Verb, TokenLocation<'a>)>,
@@ -156,10 +146,30 @@ pub enum Sentence<'a> {
         prepositional_phrases: PrepositionPhrases<'a>,
     },
     LabelDefinition(Box<(Label<'a>, TokenLocation<'a>)>),
+    DataDefinition(Box<(Label<'a>, TokenLocation<'a>)>, Data<'a>),
+    Export(Box<(Label<'a>, TokenLocation<'a>)>),
+    Import(Box<(Label<'a>, TokenLocation<'a>)>),
+    Section(Box<(&'static str, TokenLocation<'a>)>),
+    // save rbx, r12 and r13
+    List {
+        verb: Box<(Verb, TokenLocation<'a>)>,
+        objects: Vec<(Object<'a>, TokenLocation<'a>)>,
+    },
+    // atomically add 1 to qword [rax]
+    Atomic(Box<(Sentence<'a>, TokenLocation<'a>)>),
+    Note(&'a str),
     Null,
 }
 
-# [derive(Debug)]
+// sizes are in bits like Register::width
+pub(crate) enum Data<'a> {
+    String(&'a str),
+    Values(usize, Vec<Object<'a>>),
+    Reserve(usize, i64),
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug)]
 pub(crate) enum _TokenKind<'a> {
     Verb(Verb),
     Object(Object<'a>),
@@ -174,78 +184,127 @@ pub(crate) struct TokenKind<'a> {
 }
 
 impl<'a> Token<'a> {
-    pub(crate) fn inspect(&self) -> Result<TokenKind<'a>, AsmError> {
+    pub(crate) fn inspect(&self) -> Result<TokenKind<'a>, AsmError<'a>> {
         let tok = self._inspect();
-        // println!("{:?} ; {} ", self, tok);
+        let location = *self.location.borrow();
         if let Some(v) = Verb::parse(tok) {
-            Ok(TokenKind::new(_TokenKind::Verb(v), *self.location.borrow()))
-        } else if let Some(o) = Object::parse(tok) {
-            Ok(TokenKind::new(_TokenKind::Object(o),*self.location.borrow()))
+            Ok(TokenKind::new(_TokenKind::Verb(v), location))
+        } else if let Some(o) = Object::parse(tok, self.symbols).map_err(|msg| AsmError::SyntaxError(location, msg))? {
+            Ok(TokenKind::new(_TokenKind::Object(o), location))
         } else if let Some(pp) = Preposition::parse(tok) {
-            Ok(TokenKind::new(_TokenKind::Preposition(pp), *self.location.borrow()))
+            Ok(TokenKind::new(_TokenKind::Preposition(pp), location))
         } else if tok.ends_with(':') {
-            Ok(TokenKind::new(_TokenKind::LabelDef(tok.strip_suffix(':').unwrap()), *self.location.borrow()))
-        } else if tok.starts_with('<') & tok.starts_with('<') {
-            Ok(TokenKind::new(_TokenKind::LabelDef(&tok[1..tok.len() - 1]), *self.location.borrow()))
+            Ok(TokenKind::new(_TokenKind::LabelDef(tok.strip_suffix(':').unwrap()), location))
+        } else if tok.starts_with('<') & tok.ends_with('>') {
+            Ok(TokenKind::new(_TokenKind::LabelDef(&tok[1..tok.len() - 1]), location))
         } else if self.is_end() {
-            Ok(TokenKind::new(_TokenKind::EOL, *self.location.borrow()))
+            Ok(TokenKind::new(_TokenKind::EOL, location))
         } else {
-            Err(AsmError::SyntaxError(*self.location.borrow(), format!("unexpected token")))
+            Err(AsmError::SyntaxError(location, "unexpected token".to_string()))
         }
     }
 }
 
+impl<'a> Token<'a> {
+    // an object may carry a size qualifier: qword [rax]
+    fn object(&self) -> Result<Option<(Object<'a>, TokenLocation<'a>)>, AsmError<'a>> {
+        let Ok(obj) = self.inspect()?.expect_object() else {
+            return Ok(None);
+        };
+        self.next();
+        // end - start: operators between numbers and labels make a constant expression
+        if matches!(obj.0, Object::Imm(_) | Object::Label(_)) && is_operator(self._inspect()) {
+            let location = obj.1;
+            let mut expr = "";
+            while is_operator(self._inspect()) {
+                self.next();
+                if self.is_end() || Preposition::is_prep(self._inspect()) {
+                    return Err(AsmError::SyntaxError(*self.location.borrow(), "expression ends with an operator".to_string()));
+                }
+                expr = self.since(location);
+                self.next();
+            }
+            let obj = Object::constant(expr, self.symbols).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+            return Ok(Some((obj, location)));
+        }
+        if let (Object::Keyword(key), location) = &obj {
+            if let Some(size) = key.size() {
+                if let TokenKind { token: _TokenKind::Object(Object::Mem(mut mem)), .. } = self.inspect()? {
+                    self.next();
+                    mem.size = Some(size);
+                    return Ok(Some((Object::Mem(mem), *location)));
+                }
+                // qword counter => QWORD PTR [rip+counter]
+                if let TokenKind { token: _TokenKind::Object(label), .. } = self.inspect()? {
+                    if let Some(label) = bare_label(&label) {
+                        self.next();
+                        return Ok(Some((Object::Mem(Memory::rip_relative(label).sized(size)), *location)));
+                    }
+                }
+            }
+        }
+        Ok(Some(obj))
+    }
+}
+
 impl<'a> TokenKind<'a> {
     fn new(token:_TokenKind<'a>, loc: TokenLocation<'a>) -> Self {
         Self { token, location: loc}
     }
 
-    fn expect_verb(self) -> Result<(Verb, TokenLocation<'a>), AsmError<'a>> {
-        if let _TokenKind::Verb(v) = self.token {
-            Ok((v, self.location))
+    fn expect_object(self) -> Result<(Object<'a>, TokenLocation<'a>), AsmError<'a>> {
+        if let _TokenKind::Object(o) = self.token {
+            Ok((o, self.location))
         } else {
             Err(AsmError::SyntaxError(self.location,
                 format!(
-                "expected a verb, but found other: {:?}", self.token
+                "expected an object, but found other: {:?}", self.token
             )))
         }
     }
-    fn expect_object(self) -> Result<(Object<'a>, TokenLocation<'a>), AsmError<'a>> {
-        if let _TokenKind::Object(o) = self.token {
-            Ok((o, self.location))
+
+    fn expect_label(self) -> Result<(Label<'a>, TokenLocation<'a>), AsmError<'a>> {
+        if let _TokenKind::Object(Object::Label(Cow::Borrowed(l))) = self.token {
+            Ok((l, self.location))
         } else {
             Err(AsmError::SyntaxError(self.location,
                 format!(
-                "expected a verb, but found other: {:?}", self.token
+                "expected a label, but found other: {:?}", self.token
             )))
         }
     }
 
-    fn expect_preposition(self) -> Result<(Preposition, TokenLocation<'a>), AsmError<'a>> {
-        if let _TokenKind::Preposition(pp) = self.token {
-            Ok((pp, self.location))
+    fn expect_keyword(self) -> Result<(Keyword, TokenLocation<'a>), AsmError<'a>> {
+        if let _TokenKind::Object(Object::Keyword(k)) = self.token {
+            Ok((k, self.location))
         } else {
             Err(AsmError::SyntaxError(self.location,
                 format!(
-                "expected a verb, but found other: {:?}", self.token
+                "expected a keyword, but found other: {:?}", self.token
             )))
         }
     }
 
-    fn expect_label_def(self) -> Result<(Label<'a>, TokenLocation<'a>), AsmError<'a>> {
-        if let _TokenKind::LabelDef(l) = self.token {
-            Ok((l, self.location))
+    fn expect_preposition(self) -> Result<(Preposition, TokenLocation<'a>), AsmError<'a>> {
+        if let _TokenKind::Preposition(pp) = self.token {
+            Ok((pp, self.location))
         } else {
             Err(AsmError::SyntaxError(self.location,
                 format!(
-                "expected a verb, but found other: {:?}", self.token
+                "expected a preposition, but found other: {:?}", self.token
             )))
         }
     }
+
 }
 
 impl Verb {
-    fn parse<'a>(token: &'a str) -> Option<Self>
+    // jump targets, called functions and the buffers of load address, copy, fill and scan
+    fn takes_addresses(&self) -> bool {
+        matches!(self, Self::Jump | Self::Call | Self::Load | Self::Copy | Self::Fill | Self::Scan)
+    }
+
+    fn parse(token: &str) -> Option<Self>
     where
         Self: Sized,
     {
@@ -263,8 +322,37 @@ impl Verb {
             "negate" => Some(Self::Negate),
             "shift-right" => Some(Self::ShiftRight),
             "shift-left" =>Some(Self::ShiftLeft),
+            "rotate-right" => Some(Self::RotateRight),
+            "rotate-left" => Some(Self::RotateLeft),
             "call" => Some(Verb::Call),
             "compare" =>  Some(Self::Compare),
+            "test" => Some(Self::Test),
+            "check" => Some(Self::Check),
+            "bit-test" => Some(Self::BitTest),
+            "bit-test-and-set" => Some(Self::BitTestAndSet),
+            "bit-test-and-reset" => Some(Self::BitTestAndReset),
+            "bit-test-and-complement" => Some(Self::BitTestAndComplement),
+            "increment" => Some(Self::Increment),
+            "set" => Some(Self::Set),
+            "exchange" => Some(Self::Exchange),
+            "compare-and-exchange" => Some(Self::CompareAndExchange),
+            "fetch-and-add" => Some(Self::FetchAndAdd),
+            "load" => Some(Self::Load),
+            "convert" => Some(Self::Convert),
+            "copy" => Some(Self::Copy),
+            "fill" => Some(Self::Fill),
+            "scan" => Some(Self::Scan),
+            "decrement" => Some(Self::Decrement),
+            "push" => Some(Self::Push),
+            "pop" => Some(Self::Pop),
+            "save" => Some(Self::Save),
+            "restore" => Some(Self::Restore),
+
+            "define" => Some(Self::Define),
+            "reserve" => Some(Self::Reserve),
+
+            "export" => Some(Self::Export),
+            "import" => Some(Self::Import),
 
             "return" => Some(Self::Return),
             "halt" => Some(Self::Halt),
@@ -276,238 +364,306 @@ impl Verb {
     }
 }
 
-impl Register {
-    fn parse<'a>(token: &'a str) -> Option<Self> {
-        match token {
-            "al" => Some(Self::AL),
-            "bl" => Some(Self::BL),
-            "cl" => Some(Self::CL),
-            "dl" => Some(Self::DL),
-            "dil" => Some(Self::DIL),
-            "sil" => Some(Self::SIL),
-            "bpl" => Some(Self::BPL),
-            "spl" => Some(Self::SPL),
-            "r8b" => Some(Self::R8B),
-            "r9b" => Some(Self::R9B),
-            "r10b" => Some(Self::R10B),
-            "r11b" => Some(Self::R11B),
-            "r12b" => Some(Self::R12B),
-            "r13b" => Some(Self::R13B),
-            "r14b" => Some(Self::R14B),
-            "r15b" => Some(Self::R15B),
-            "ax" => Some(Self::AX),
-            "bx" => Some(Self::BX),
-            "cx" => Some(Self::CX),
-            "dx" => Some(Self::DX),
-            "di" => Some(Self::DI),
-            "si" => Some(Self::SI),
-            "bp" => Some(Self::BP),
-            "sp" => Some(Self::SP),
-            "r8w" => Some(Self::R8W),
-            "r9w" => Some(Self::R9W),
-            "r10w" => Some(Self::R10W),
-            "r11w" => Some(Self::R11W),
-            "r12w" => Some(Self::R12W),
-            "r13w" => Some(Self::R13W),
-            "r14w" => Some(Self::R14W),
-            "r15w" => Some(Self::R15W),
-            "eax" => Some(Self::EAX),
-            "ebx" => Some(Self::EBX),
-            "ecx" => Some(Self::ECX),
-            "edx" => Some(Self::EDX),
-            "edi" => Some(Self::EDI),
-            "esi" => Some(Self::ESI),
-            "ebp" => Some(Self::EBP),
-            "esp" => Some(Self::ESP),
-            "r8d" => Some(Self::R8D),
-            "r9d" => Some(Self::R9D),
-            "r10d" => Some(Self::R10D),
-            "r11d" => Some(Self::R11D),
-            "r12d" => Some(Self::R12D),
-            "r13d" => Some(Self::R13D),
-            "r14d" => Some(Self::R14D),
-            "r15d" => Some(Self::R15D),
-            "rax" => Some(Self::RAX),
-            "rbx" => Some(Self::RBX),
-            "rcx" => Some(Self::RCX),
-            "rdx" => Some(Self::RDX),
-            "rdi" => Some(Self::RDI),
-            "rsi" => Some(Self::RSI),
-            "rbp" => Some(Self::RBP),
-            "rsp" => Some(Self::RSP),
-            "r8" => Some(Self::R8),
-            "r9" => Some(Self::R9),
-            "r10" => Some(Self::R10),
-            "r11" => Some(Self::R11),
-            "r12" => Some(Self::R12),
-            "r13" => Some(Self::R13),
-            "r14" => Some(Self::R14),
-            "r15" => Some(Self::R15),
-            "xmm0" => Some(Self::XMM0),
-            "xmm1" => Some(Self::XMM1),
-            "xmm2" => Some(Self::XMM2),
-            "xmm3" => Some(Self::XMM3),
-            "xmm4" => Some(Self::XMM4),
-            "xmm5" => Some(Self::XMM5),
-            "xmm6" => Some(Self::XMM6),
-            "xmm7" => Some(Self::XMM7),
-            _ => None,
-        }
-    }
-}
-
 impl Keyword {
-    fn parse<'a>(token: &'a str) ->  Option<Self> {
+    fn parse(token: &str) -> Option<Self> {
         match token {
             "single-precision-float" => Some(Self::SinglePrecisionFloat),
             "double-precision-float" => Some(Self::DoublePrecisionFloat),
+            "packed-singles" => Some(Self::PackedSingles),
+            "packed-doubles" => Some(Self::PackedDoubles),
             "signed" =>  Some(Self::Signed),
+            "unsigned" => Some(Self::Unsigned),
             "zero-extended" => Some(Self::ZeroExtened),
+            "string" => Some(Self::String),
+            "bits" => Some(Self::Bits),
+            "address" => Some(Self::Address),
+            "remainder" => Some(Self::Remainder),
+            "byte" | "bytes" => Some(Self::Byte),
+            "word" | "words" => Some(Self::Word),
+            "dword" | "dwords" | "doubleword" | "doublewords" => Some(Self::DoubleWord),
+            "qword" | "qwords" | "quadword" | "quadwords" => Some(Self::QuadWord),
             "=" => Some(Self::E),
             "!=" => Some(Self::NE),
             "<" => Some(Self::L),
             "<=" => Some(Self::LE),
             ">" => Some(Self::G),
             ">=" => Some(Self::GE),
+            "below" => Some(Self::B),
+            "below-or-equal" => Some(Self::BE),
+            "above" => Some(Self::A),
+            "above-or-equal" => Some(Self::AE),
+            "zero" => Some(Self::Zero),
+            "not-zero" => Some(Self::NotZero),
+            "carry" => Some(Self::Carry),
+            "not-carry" => Some(Self::NotCarry),
+            "overflow" => Some(Self::Overflow),
+            "not-overflow" => Some(Self::NotOverflow),
+            "sign" => Some(Self::Sign),
+            "not-sign" => Some(Self::NotSign),
+            "parity" => Some(Self::Parity),
+            "not-parity" => Some(Self::NotParity),
             _ => None,
         }
     }
 }
 
-impl fmt::Display for Register {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let reg = match self {
-            Register::AL => "al",
-            Register::BL => "bl",
-            Register::CL => "cl",
-            Register::DL => "dl",
-            Register::DIL => "dil",
-            Register::SIL => "sil",
-            Register::BPL => "bpl",
-            Register::SPL => "spl",
-            Register::R8B => "r8b",
-            Register::R9B => "r9b",
-            Register::R10B => "r10b",
-            Register::R11B => "r11b",
-            Register::R12B => "r12b",
-            Register::R13B => "r13b",
-            Register::R14B => "r14b",
-            Register::R15B => "r15b",
-            Register::AX => "ax",
-            Register::BX => "bx",
-            Register::CX => "cx",
-            Register::DX => "dx",
-            Register::DI => "di",
-            Register::SI => "si",
-            Register::BP => "bp",
-            Register::SP => "sp",
-            Register::R8W => "r8w",
-            Register::R9W => "r9w",
-            Register::R10W => "r10w",
-            Register::R11W => "r11w",
-            Register::R12W => "r12w",
-            Register::R13W => "r13w",
-            Register::R14W => "r14w",
-            Register::R15W => "r15w",
-            Register::EAX => "eax",
-            Register::EBX => "ebx",
-            Register::ECX => "ecx",
-            Register::EDX => "edx",
-            Register::EDI => "edi",
-            Register::ESI => "esi",
-            Register::EBP => "ebp",
-            Register::ESP => "esp",
-            Register::R8D => "r8d",
-            Register::R9D => "r9d",
-            Register::R10D => "r10d",
-            Register::R11D => "r11d",
-            Register::R12D => "r12d",
-            Register::R13D => "r13d",
-            Register::R14D => "r14d",
-            Register::R15D => "r15d",
-            Register::RAX => "rax",
-            Register::RBX => "rbx",
-            Register::RCX => "rcx",
-            Register::RDX => "rdx",
-            Register::RDI => "rdi",
-            Register::RSI => "rsi",
-            Register::RBP => "rbp",
-            Register::RSP => "rsp",
-            Register::R8 => "r8",
-            Register::R9 => "r9",
-            Register::R10 => "r10",
-            Register::R11 => "r11",
-            Register::R12 => "r12",
-            Register::R13 => "r13",
-            Register::R14 => "r14",
-            Register::R15 => "r15",
-            Register::XMM0 => "xmm0",
-            Register::XMM1 => "xmm1",
-            Register::XMM2 => "xmm2",
-            Register::XMM3 => "xmm3",
-            Register::XMM4 => "xmm4",
-            Register::XMM5 => "xmm5",
-            Register::XMM6 => "xmm6",
-            Register::XMM7 => "xmm7",
-        };
-        write!(f, "{}", reg)
+impl Keyword {
+    pub(crate) fn size(&self) -> Option<usize> {
+        match self {
+            Self::Byte => Some(8),
+            Self::Word => Some(16),
+            Self::DoubleWord => Some(32),
+            Self::QuadWord => Some(64),
+            _ => None,
+        }
     }
 }
 
 impl<'b> Object<'b> {
-    fn parse(token: &'b str) -> Option<Self>
+    fn parse(token: &'b str, symbols: &Symbols) -> Result<Option<Self>, String>
     where
         Self: Sized,
     {
-        // little weird?
         if token.starts_with('[') {
-            // base + idx * scale + dis
-            if let Some(mem) = Memory::parse(process(token)) {
-                return Some(Self::Mem(mem));
-            } else {
-                return None;
-            }
-        } else if let Ok(num) = token.parse::<i64>() {
-            return Some(Self::Imm(num));
-        } else if let Some(reg) = Register::parse(token) {
-            return Some(Self::Reg(reg));
+            Ok(Some(Self::Mem(Memory::parse(token, symbols)?)))
+        } else if is_expression(token) {
+            Ok(Some(Self::constant(token, symbols)?))
+        } else if let Some(num) = parse_number(token)? {
+            Ok(Some(Self::Imm(num)))
+        } else if let Some(value) = symbols.constant(token) {
+            Ok(Some(Self::Imm(value)))
+        } else if let Some(reg) = symbols.register(token) {
+            Ok(Some(Self::Reg(reg)))
         } else if let Some(key) = Keyword::parse(token) {
-            return Some(Self::Keyword(key));
-        } else if !(token.ends_with(':') | token.is_empty() | Preposition::is_prep(token) | token.starts_with('<') | token.starts_with('<')) {
-            return Some(Self::Label(&token[..token.len()]));
+            Ok(Some(Self::Keyword(key)))
+        } else if !(token.ends_with(':') | token.is_empty() | Preposition::is_prep(token) | token.starts_with('<') | token.starts_with('"')) {
+            Ok(Some(Self::Label(Cow::Borrowed(token))))
         } else {
-            None
+            Ok(None)
+        }
+    }
+
+    // expressions over labels are left for the assembler like a label
+    fn constant(expr: &'b str, symbols: &Symbols) -> Result<Self, String> {
+        Ok(match evaluate(expr, symbols)? {
+            Some(value) => Self::Imm(value),
+            None => Self::Label(substitute(expr, symbols)?),
+        })
+    }
+
+    pub(crate) fn size(&self) -> Option<usize> {
+        match self {
+            Self::Reg(reg) => Some(reg.width()),
+            Self::Mem(mem) => mem.size,
+            _ => None,
         }
     }
 }
 
-fn process<'a>(token: &'a str) -> Vec<String> {
+// (4 * 8 + 1), -(2 * 3) and 4*8, but not -1, '+' or hyphenated keywords like not-zero
+fn is_expression(token: &str) -> bool {
+    let body = token.trim_start_matches('-');
+    body.starts_with('(') || (!body.starts_with('\'') && body.contains(['+', '-', '*', '/', '%']) && Keyword::parse(token).is_none())
+}
+
+// a single name like counter, not an expression like end - start
+fn bare_label<'a>(obj: &Object<'a>) -> Option<&'a str> {
+    match obj {
+        Object::Label(Cow::Borrowed(label)) if names(label) == [*label] => Some(label),
+        _ => None,
+    }
+}
+
+// signed or unsigned values of the given width
+fn fits(value: i128, size: usize) -> bool {
+    (-(1i128 << (size - 1))..(1i128 << size)).contains(&value)
+}
+
+fn process(token: &str) -> Vec<String> {
     token
-        .replace("[", " [ ")
-        .replace("]", " ] ")
-        .replace("*", " * ")
-        .replace("+", " + ")
-        .replace("-", " - ")
+        .replace('[', " [ ")
+        .replace(']', " ] ")
+        .replace('*', " * ")
+        .replace('+', " + ")
+        .replace('-', " - ")
         .split_whitespace()
-        .collect::<Vec<&str>>()
-        .into_iter()
         .map(|x| x.to_string())
         .collect()
 }
 
-impl Memory {
-    fn parse(token: Vec<String>) -> Option<Self> {
-        if let Some(reg) = Register::parse(&token[1]) {
-            return Some(Memory { base: reg });
-        } else {
-            None
+impl<'a> Memory<'a> {
+    pub(crate) fn size(&self) -> Option<usize> {
+        self.size
+    }
+
+    // a bare label as memory: counter => [rip+counter]
+    pub(crate) fn rip_relative(label: &'a str) -> Self {
+        Self { base: None, index: None, scale: 1, displacement: 0, label: Some(label), size: None }
+    }
+
+    pub(crate) fn sized(self, size: usize) -> Self {
+        Self { size: Some(size), ..self }
+    }
+
+    pub(crate) fn needs_rex(&self) -> bool {
+        self.base.iter().chain(self.index.iter()).any(|reg| reg.needs_rex())
+    }
+
+    pub(crate) fn uses(&self, reg: Register) -> bool {
+        self.base.iter().chain(self.index.iter()).any(|r| r.parent() == reg.parent())
+    }
+
+    fn parse(source: &'a str, symbols: &Symbols) -> Result<Self, String> {
+        let token = process(source);
+        if token.len() < 3 || token[0] != "[" || token[token.len() - 1] != "]" {
+            return Err("memory operand must look like [base + index * scale + displacement]".to_string());
+        }
+
+        // split "[ rax + rcx * 8 - ( 2 + 1 ) ]" into signed terms: (+, [rax]), (+, [rcx, *, 8]), (-, [(2, +, 1)])
+        let mut terms: Vec<(bool, Vec<&str>)> = Vec::new();
+        let mut negative = false;
+        let mut factors: Vec<&str> = Vec::new();
+        let mut depth = 0;
+        for tok in &token[1..token.len() - 1] {
+            match tok.as_str() {
+                "+" | "-" if depth == 0 => {
+                    if !factors.is_empty() {
+                        terms.push((negative, std::mem::take(&mut factors)));
+                    } else if !terms.is_empty() {
+                        return Err(format!("unexpected '{}' in memory operand", tok));
+                    }
+                    negative = tok == "-";
+                }
+                "]" | "[" => return Err("nested brackets in memory operand".to_string()),
+                _ => {
+                    depth += tok.matches('(').count() as i32 - tok.matches(')').count() as i32;
+                    factors.push(tok);
+                }
+            }
+        }
+        if depth != 0 {
+            return Err("unbalanced parentheses in memory operand".to_string());
+        }
+        if factors.is_empty() {
+            return Err("memory operand ends with an operator".to_string());
+        }
+        terms.push((negative, factors));
+
+        let mut mem = Memory { base: None, index: None, scale: 1, displacement: 0, label: None, size: None };
+        for (negative, factors) in terms {
+            // a term without registers is part of the displacement: [rbp - 8*3], [table + 8]
+            if !factors.iter().any(|factor| symbols.register(factor).is_some()) {
+                let Some(num) = evaluate(&factors.join(" "), symbols)? else {
+                    let [label] = factors.as_slice() else {
+                        return Err(format!("'{}' mixes a label with arithmetic in memory operand", factors.join(" ")));
+                    };
+                    if negative {
+                        return Err(format!("label {} can't be subtracted in memory operand", label));
+                    }
+                    if mem.label.is_some() {
+                        return Err("memory operand takes at most one label".to_string());
+                    }
+                    // borrow the name from the source so it lives as long as the sentence
+                    let start = source.find(label).unwrap();
+                    mem.label = Some(&source[start..start + label.len()]);
+                    continue;
+                };
+                let num = i64::try_from(num).map_err(|_| format!("'{}' doesn't fit in 64 bits", factors.join(" ")))?;
+                mem.displacement = if negative {
+                    mem.displacement.checked_sub(num)
+                } else {
+                    mem.displacement.checked_add(num)
+                }
+                .ok_or("displacement overflows".to_string())?;
+                continue;
+            }
+            let (reg, scale) = match factors.as_slice() {
+                [single] => (symbols.register(single).unwrap(), None),
+                [lhs, "*", rhs] => match (symbols.register(lhs), symbols.register(rhs)) {
+                    (Some(reg), None) => (reg, Some(rhs.parse::<u8>().map_err(|_| format!("invalid scale '{}'", rhs))?)),
+                    (None, Some(reg)) => (reg, Some(lhs.parse::<u8>().map_err(|_| format!("invalid scale '{}'", lhs))?)),
+                    _ => return Err(format!("'{} * {}' must be a register times a scale", lhs, rhs)),
+                },
+                _ => return Err(format!("invalid term '{}' in memory operand", factors.join(" "))),
+            };
+            if negative {
+                return Err(format!("register {} can't be subtracted in memory operand", reg));
+            }
+            if reg.class() != RegisterClass::GeneralPurpose || !matches!(reg.width(), 32 | 64) {
+                return Err(format!("{} can't be used for addressing", reg));
+            }
+            match (scale, mem.base, mem.index) {
+                (None, None, _) => mem.base = Some(reg),
+                (_, _, None) => {
+                    mem.index = Some(reg);
+                    mem.scale = scale.unwrap_or(1);
+                }
+                _ => return Err("memory operand takes at most a base and an index register".to_string()),
+            }
+        }
+
+        if let Some(label) = mem.label.filter(|_| mem.base.is_some() || mem.index.is_some()) {
+            // [table + rcx*8] needs an absolute address, which doesn't link as PIE
+            return Err(format!("label {} can't be used with registers; load address of {} to a register first", label, label));
+        }
+        if ![1, 2, 4, 8].contains(&mem.scale) {
+            return Err(format!("scale must be 1, 2, 4 or 8, but found {}", mem.scale));
+        }
+        if let (Some(base), Some(index)) = (mem.base, mem.index) {
+            if base.width() != index.width() {
+                return Err(format!("base {} and index {} must have the same width", base, index));
+            }
+        }
+        if let Some(index) = mem.index {
+            if index.parent() == Register::RSP {
+                // [rax + rsp] is fine as long as rsp can be the base instead
+                if mem.scale != 1 || mem.base.is_some_and(|base| base.parent() == Register::RSP) {
+                    return Err(format!("{} can't be used as an index", index));
+                }
+                mem.index = mem.base;
+                mem.base = Some(index);
+            }
+        }
+        if i32::try_from(mem.displacement).is_err() {
+            return Err(format!("displacement {} doesn't fit in 32 bits", mem.displacement));
         }
+        Ok(mem)
     }
 }
 
-impl fmt::Display for Memory {
+impl<'a> fmt::Display for Memory<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[{}]", self.base)
+        let mut address = String::new();
+        if let Some(label) = self.label {
+            // position independent: [rip+counter]
+            address.push_str(&format!("rip+{}", label));
+        }
+        if let Some(base) = self.base {
+            address.push_str(&base.to_string());
+        }
+        if let Some(index) = self.index {
+            if !address.is_empty() {
+                address.push('+');
+            }
+            address.push_str(&index.to_string());
+            if self.scale != 1 {
+                address.push_str(&format!("*{}", self.scale));
+            }
+        }
+        if self.displacement != 0 || address.is_empty() {
+            if !address.is_empty() && self.displacement >= 0 {
+                address.push('+');
+            }
+            address.push_str(&self.displacement.to_string());
+        }
+        let ptr = match self.size {
+            Some(8) => "BYTE PTR ",
+            Some(16) => "WORD PTR ",
+            Some(32) => "DWORD PTR ",
+            Some(64) => "QWORD PTR ",
+            _ => "",
+        };
+        write!(f, "{}[{}]", ptr, address)
     }
 }
 
@@ -524,7 +680,7 @@ impl<'a> fmt::Display for Object<'a> {
 }
 
 impl Preposition {
-    fn parse<'a>(token: &'a str) -> Option<Self>
+    fn parse(token: &str) -> Option<Self>
     where
         Self: Sized,
     {
@@ -535,6 +691,11 @@ impl Preposition {
             "as" => Some(Self::As),
             "with" => Some(Self::With),
             "if" => Some(Self::If),
+            "in" => Some(Self::In),
+            "of" => Some(Self::Of),
+            "at" => Some(Self::At),
+            "for" => Some(Self::For),
+            "keep" => Some(Self::Keep),
             _ => None,
         }
     }
@@ -543,12 +704,13 @@ impl Preposition {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct PrepositionPhrases<'a> {
     phrases: RefCell<HashMap<Preposition, (Object<'a>, TokenLocation<'a>)>>,
 }
 
 impl<'a> PrepositionPhrases<'a> {
-    fn parse(token: &'a Token) -> Result<Self, AsmError<'a>>
+    fn parse(token: &Token<'a>) -> Result<Self, AsmError<'a>>
     where
         Self: Sized,
     {
@@ -557,8 +719,10 @@ impl<'a> PrepositionPhrases<'a> {
         while !token.is_end() {
             let prep = token.inspect()?.expect_preposition()?;
             token.next();
-            let obj = token.inspect()?.expect_object()?;
-            token.next();
+            let obj = match token.object()? {
+                Some(obj) => obj,
+                None => token.inspect()?.expect_object()?,
+            };
             map.insert(prep.0, obj);
         }
         Ok(Self { phrases: RefCell::new(map) })
@@ -568,6 +732,14 @@ impl<'a> PrepositionPhrases<'a> {
         self.phrases.borrow_mut().remove(&pp)
     }
 
+    pub(crate) fn insert(&self, pp: Preposition, o: (Object<'a>, TokenLocation<'a>)) {
+        self.phrases.borrow_mut().insert(pp, o);
+    }
+
+    pub(crate) fn matches(&self, pp: Preposition, f: impl Fn(&Object<'a>) -> bool) -> bool {
+        self.phrases.borrow().get(&pp).is_some_and(|(o, _)| f(o))
+    }
+
     pub(crate) fn have(&self, pp: Preposition) -> bool {
         self.phrases.borrow().contains_key(&pp)
     }
@@ -575,45 +747,337 @@ impl<'a> PrepositionPhrases<'a> {
     pub(crate) fn have_no_phrases(&self) -> bool {
         self.phrases.borrow().is_empty()
     }
+
+    // a label names the data stored there: move counter to rax => mov rax, [rip+counter]
+    fn labels_as_memory(&self, object: &mut Option<Box<(Object<'a>, TokenLocation<'a>)>>) {
+        let mut phrases = self.phrases.borrow_mut();
+        for (obj, _) in object.iter_mut().map(|o| &mut **o).chain(phrases.values_mut()) {
+            if let Some(label) = bare_label(obj) {
+                *obj = Object::Mem(Memory::rip_relative(label));
+            }
+        }
+    }
+
+    // `move 1 to [rax] as quadword` sizes the memory operand like `move 1 to qword [rax]`
+    fn apply_size(&self, object: &mut Option<Box<(Object<'a>, TokenLocation<'a>)>>) -> Result<(), AsmError<'a>> {
+        let (size, location) = match self.phrases.borrow().get(&Preposition::As) {
+            Some((Object::Keyword(key), location)) if key.size().is_some() => (key.size(), *location),
+            _ => return Ok(()),
+        };
+        self.consume(Preposition::As);
+
+        let mut sized = false;
+        let mut phrases = self.phrases.borrow_mut();
+        for (obj, _) in object.iter_mut().map(|o| &mut **o).chain(phrases.values_mut()) {
+            if let Object::Mem(mem) = obj {
+                if mem.size.is_none() {
+                    mem.size = size;
+                    sized = true;
+                }
+            }
+        }
+        if sized {
+            Ok(())
+        } else {
+            Err(AsmError::SyntaxError(location, "size qualifier needs a memory operand without a size".to_string()))
+        }
+    }
 }
 
 impl<'a> Sentence<'a> {
-    pub fn parse(token: &'a Token<'a>) -> Result<Self, AsmError<'a>>
+    pub fn parse(token: &Token<'a>) -> Result<Self, AsmError<'a>>
     where
         Self: Sized,
     {
-        match token.inspect() {
-            Ok(TokenKind { token: _TokenKind::Verb(verb), location }) => {
+        if token._inspect() == "forget" {
+            token.next();
+            let location = *token.location.borrow();
+            token.symbols.forget_alias(token._inspect()).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+            token.next();
+            expect_end(token)?;
+            return Ok(Self::Null);
+        }
+        if token._inspect() == "let" {
+            token.next();
+            return Self::parse_constant(token, "be");
+        }
+        if token._inspect() == "atomically" {
+            let location = *token.location.borrow();
+            token.next();
+            return Ok(Self::Atomic(Box::new((Self::parse(token)?, location))));
+        }
+        match token.inspect()? {
+            TokenKind { token: _TokenKind::Verb(verb @ (Verb::Define | Verb::Reserve)), .. } => {
                 token.next();
-                // let object = if let Ok(obj) = token.inspect()?.expect_object() {
-                //     token.next();
-                //     Some(Box::new(obj))
-                // } else {
-                //     None
-                // };
-                let object =  match token.inspect()?.expect_object().ok() {
-                    Some(obj) => {
+                Self::parse_data(verb, token)
+            }
+            TokenKind { token: _TokenKind::Verb(verb @ (Verb::Export | Verb::Import)), .. } => {
+                token.next();
+                let label = Box::new(token.inspect()?.expect_label()?);
+                token.next();
+                expect_end(token)?;
+                match verb {
+                    Verb::Export => Ok(Self::Export(label)),
+                    _ => Ok(Self::Import(label)),
+                }
+            }
+            TokenKind { token: _TokenKind::Verb(verb @ (Verb::Save | Verb::Restore)), location } => {
+                token.next();
+                Ok(Self::List {
+                    verb: Box::new((verb, location)),
+                    objects: Self::parse_list(token)?,
+                })
+            }
+            TokenKind { token: _TokenKind::Verb(verb), location } => {
+                token.next();
+                let mut object = token.object()?.map(Box::new);
+                // call rcx counter
+                if let (Verb::Call, Some((Object::Reg(reg), _))) = (&verb, object.as_deref()) {
+                    if !token.is_end() && !Preposition::is_prep(token._inspect()) {
+                        return Self::parse_alias(token, *reg);
+                    }
+                }
+                // copy rcx bytes: a unit right after the object is kept as its 'as' phrase
+                let unit = match token.inspect()? {
+                    TokenKind { token: _TokenKind::Object(Object::Keyword(key)), location } if object.is_some() && key.size().is_some() => {
                         token.next();
-                        Some(Box::new(obj))
-                    },
-                    None => None
+                        Some((Object::Keyword(key), location))
+                    }
+                    _ => None,
                 };
-
-                
-                
-                            // assert!(token.seq.len() == token.location() + token.len);
-                            // assert!(token.len == 0);
+                let prepositional_phrases = PrepositionPhrases::parse(token)?;
+                if !verb.takes_addresses() {
+                    prepositional_phrases.labels_as_memory(&mut object);
+                }
+                prepositional_phrases.apply_size(&mut object)?;
+                if let Some(unit) = unit {
+                    if prepositional_phrases.have(Preposition::As) {
+                        return Err(AsmError::SyntaxError(unit.1, "unit and 'as' phrase can't be used together".to_string()));
+                    }
+                    prepositional_phrases.insert(Preposition::As, unit);
+                }
                 Ok(Self::Sentence {
                     verb: Box::new((verb, location)),
-                    object: object,
-                    prepositional_phrases: PrepositionPhrases::parse(&token)?,
+                    object,
+                    prepositional_phrases,
                 })
             }
-            Ok(TokenKind{ token: _TokenKind::LabelDef(label), location}) => {
+            // note: this sentence is kept as a comment in the output
+            TokenKind { token: _TokenKind::LabelDef("note"), .. } => {
+                token.next();
+                Ok(Self::Note(token.rest()))
+            }
+            TokenKind { token: _TokenKind::LabelDef(label), location } => {
+                token.symbols.define_label(label).map_err(|msg| AsmError::SyntaxError(location, msg))?;
                 Ok(Self::LabelDefinition(Box::new((label, location))))
             }
-            Ok(TokenKind { token:_TokenKind::EOL, .. }) => Ok(Self::Null),
-            _ => Err(AsmError::SyntaxError(*token.location.borrow(),format!("something is wrong1")))
+            // in section data
+            TokenKind { token: _TokenKind::Preposition(Preposition::In), location } => {
+                token.next();
+                if token._inspect() != "section" {
+                    return Err(AsmError::SyntaxError(*token.location.borrow(), "expected 'section'".to_string()));
+                }
+                token.next();
+                let name = match token._inspect().trim_start_matches('.') {
+                    "text" => ".text",
+                    "data" => ".data",
+                    "rodata" => ".rodata",
+                    "bss" => ".bss",
+                    _ => return Err(AsmError::SyntaxError(*token.location.borrow(), "section must be text, data, rodata or bss".to_string())),
+                };
+                token.next();
+                expect_end(token)?;
+                Ok(Self::Section(Box::new((name, location))))
+            }
+            TokenKind { token: _TokenKind::EOL, .. } => Ok(Self::Null),
+            TokenKind { location, .. } => Err(AsmError::SyntaxError(location, "a sentence must start with a verb or a label".to_string()))
+        }
+    }
+
+    // rbx, r12 and r13
+    fn parse_list(token: &Token<'a>) -> Result<Vec<(Object<'a>, TokenLocation<'a>)>, AsmError<'a>> {
+        let mut objects = Vec::new();
+        while !token.is_end() {
+            let location = *token.location.borrow();
+            for item in token._inspect().split(',').filter(|item| !item.is_empty() && *item != "and") {
+                match Object::parse(item, token.symbols).map_err(|msg| AsmError::SyntaxError(location, msg))? {
+                    Some(obj @ Object::Reg(_)) => objects.push((obj, location)),
+                    _ => return Err(AsmError::SyntaxError(location, format!("expected a register, but found '{}'", item))),
+                }
+            }
+            token.next();
+        }
+        if objects.is_empty() {
+            return Err(AsmError::SyntaxError(*token.location.borrow(), "expected a list of registers".to_string()));
+        }
+        Ok(objects)
+    }
+
+    // define message as string "hello\n"
+    // define table as quadwords 1 2 3
+    // reserve 64 bytes as buffer
+    fn parse_data(verb: Verb, token: &Token<'a>) -> Result<Self, AsmError<'a>> {
+        let sentence = match verb {
+            Verb::Define if token._inspect() == "constant" => {
+                token.next();
+                return Self::parse_constant(token, "as");
+            }
+            Verb::Define => {
+                let label = expect_new_label(token)?;
+                token.next();
+                expect_as(token)?;
+                let (kind, location) = token.inspect()?.expect_keyword()?;
+                token.next();
+                let data = match (kind.size(), kind) {
+                    (_, Keyword::String) => {
+                        let literal = token._inspect();
+                        if literal.len() < 2 || !literal.starts_with('"') || !literal.ends_with('"') {
+                            return Err(AsmError::SyntaxError(*token.location.borrow(), "expected a string literal".to_string()));
+                        }
+                        token.next();
+                        Data::String(literal)
+                    }
+                    (Some(size), _) => {
+                        let mut values = Vec::new();
+                        while !token.is_end() {
+                            match token.inspect()?.expect_object()? {
+                                (Object::Imm(value), location) if !fits(value, size) => {
+                                    return Err(AsmError::SyntaxError(location, format!("{} doesn't fit in {} bits", value, size)))
+                                }
+                                (obj @ (Object::Imm(_) | Object::Label(_)), _) => values.push(obj),
+                                (_, location) => return Err(AsmError::SyntaxError(location, "data can only hold numbers and labels".to_string())),
+                            }
+                            token.next();
+                        }
+                        if values.is_empty() {
+                            return Err(AsmError::SyntaxError(location, "data definition needs at least one value".to_string()));
+                        }
+                        Data::Values(size, values)
+                    }
+                    (None, _) => return Err(AsmError::SyntaxError(location, "define takes string, bytes, words, dwords or quadwords".to_string())),
+                };
+                Self::DataDefinition(Box::new(label), data)
+            }
+            Verb::Reserve => {
+                let count = match token.inspect()?.expect_object()? {
+                    (Object::Imm(count), _) if count > 0 && count <= i128::from(u32::MAX) => count as i64,
+                    (_, location) => return Err(AsmError::SyntaxError(location, "reserve needs a positive count".to_string())),
+                };
+                token.next();
+                let (unit, location) = token.inspect()?.expect_keyword()?;
+                let size = unit
+                    .size()
+                    .ok_or(AsmError::SyntaxError(location, "reserve takes bytes, words, dwords or quadwords".to_string()))?;
+                token.next();
+                expect_as(token)?;
+                let label = expect_new_label(token)?;
+                token.next();
+                Self::DataDefinition(Box::new(label), Data::Reserve(size, count))
+            }
+            _ => unreachable!(),
+        };
+        expect_end(token)?;
+        Ok(sentence)
+    }
+}
+
+impl<'a> Sentence<'a> {
+    // every label the sentence refers to, including those inside expressions
+    pub(crate) fn references(&self) -> Vec<(String, TokenLocation<'a>)> {
+        let mut objects: Vec<(&Object<'a>, TokenLocation<'a>)> = Vec::new();
+        let phrases;
+        match self {
+            Self::Sentence { object, prepositional_phrases, .. } => {
+                objects.extend(object.iter().map(|o| (&o.0, o.1)));
+                phrases = prepositional_phrases.phrases.borrow();
+                objects.extend(phrases.values().map(|(o, loc)| (o, *loc)));
+            }
+            Self::DataDefinition(label, Data::Values(_, values)) => objects.extend(values.iter().map(|o| (o, label.1))),
+            Self::Atomic(atomic) => return atomic.0.references(),
+            _ => (),
+        }
+        objects
+            .into_iter()
+            .flat_map(|(o, loc)| match o {
+                Object::Label(expr) => names(expr).into_iter().map(|name| (name.to_string(), loc)).collect(),
+                Object::Mem(mem) => mem.label.into_iter().map(|name| (name.to_string(), loc)).collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    // call rcx counter, until `forget counter`
+    fn parse_alias(token: &Token<'a>, register: Register) -> Result<Self, AsmError<'a>> {
+        let location = *token.location.borrow();
+        let name = token._inspect();
+        let shadowed = if Register::parse(name).is_some() {
+            Some("register")
+        } else if Keyword::parse(name).is_some() {
+            Some("keyword")
+        } else if Preposition::is_prep(name) {
+            Some("preposition")
+        } else if Verb::parse(name).is_some() {
+            Some("verb")
+        } else {
+            None
+        };
+        if let Some(kind) = shadowed {
+            return Err(AsmError::SyntaxError(location, format!("alias {} shadows a {}", name, kind)));
+        }
+        token.symbols.check_unused(name).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+        if !matches!(token.inspect()?.token, _TokenKind::Object(Object::Label(_))) {
+            return Err(AsmError::SyntaxError(location, format!("{} can't be an alias", name)));
+        }
+        token.symbols.define_alias(name, register).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+        token.next();
+        expect_end(token)?;
+        Ok(Self::Null)
+    }
+
+    // let SYS_WRITE be 1
+    // define constant SYS_WRITE as 1
+    // constants are replaced by their values wherever a number can be written
+    fn parse_constant(token: &Token<'a>, link: &str) -> Result<Self, AsmError<'a>> {
+        let location = *token.location.borrow();
+        token.symbols.check_unused(token._inspect()).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+        let (name, location) = token.inspect()?.expect_label()?;
+        token.next();
+        if token._inspect() != link {
+            return Err(AsmError::SyntaxError(*token.location.borrow(), format!("expected '{}'", link)));
+        }
+        token.next();
+        let value = match token.object()? {
+            Some((Object::Imm(value), _)) => value,
+            Some((_, location)) => return Err(AsmError::SyntaxError(location, "constant must be a number".to_string())),
+            None => return Err(AsmError::SyntaxError(*token.location.borrow(), "expected a value".to_string())),
+        };
+        expect_end(token)?;
+        token.symbols.define_constant(name, value, location.line()).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+        Ok(Self::Null)
+    }
+}
+
+// a data label can't reuse the name of a constant
+fn expect_new_label<'a>(token: &Token<'a>) -> Result<(Label<'a>, TokenLocation<'a>), AsmError<'a>> {
+    let location = *token.location.borrow();
+    token.symbols.define_label(token._inspect()).map_err(|msg| AsmError::SyntaxError(location, msg))?;
+    token.inspect()?.expect_label()
+}
+
+fn expect_end<'a>(token: &Token<'a>) -> Result<(), AsmError<'a>> {
+    if token.is_end() {
+        Ok(())
+    } else {
+        Err(AsmError::SyntaxError(*token.location.borrow(), "unexpected token at the end of sentence".to_string()))
+    }
+}
+
+fn expect_as<'a>(token: &Token<'a>) -> Result<(), AsmError<'a>> {
+    match token.inspect()?.expect_preposition()? {
+        (Preposition::As, _) => {
+            token.next();
+            Ok(())
         }
+        (_, location) => Err(AsmError::SyntaxError(location, "expected 'as'".to_string())),
     }
 }
@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use super::Register;
+
+// names defined so far: labels, `let` constants and register aliases share one namespace
+#[derive(Debug, Default)]
+pub struct Symbols {
+    // values and the lines defining them
+    constants: RefCell<HashMap<String, (i128, usize)>>,
+    labels: RefCell<HashSet<String>>,
+    aliases: RefCell<HashMap<String, Register>>,
+}
+
+impl Symbols {
+    // a register by its name or by an alias from `call rcx counter`
+    pub(crate) fn register(&self, name: &str) -> Option<Register> {
+        Register::parse(name).or_else(|| self.aliases.borrow().get(name).copied())
+    }
+
+    fn is_alias(&self, name: &str) -> bool {
+        self.aliases.borrow().contains_key(name)
+    }
+
+    pub(crate) fn constant(&self, name: &str) -> Option<i128> {
+        self.constants.borrow().get(name).map(|(value, _)| *value)
+    }
+
+    pub(crate) fn constant_line(&self, name: &str) -> Option<usize> {
+        self.constants.borrow().get(name).map(|(_, line)| *line)
+    }
+
+    pub(crate) fn is_label(&self, name: &str) -> bool {
+        self.labels.borrow().contains(name)
+    }
+
+    // constants and aliases need a name nothing else uses
+    pub(crate) fn check_unused(&self, name: &str) -> Result<(), String> {
+        if self.is_label(name) {
+            return Err(format!("{} is already defined as a label", name));
+        }
+        self.check_not_constant_or_alias(name)
+    }
+
+    fn check_not_constant_or_alias(&self, name: &str) -> Result<(), String> {
+        if self.constant(name).is_some() {
+            return Err(format!("{} is already defined as a constant", name));
+        }
+        if self.is_alias(name) {
+            return Err(format!("{} is already an alias", name));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn define_alias(&self, name: &str, register: Register) -> Result<(), String> {
+        self.check_unused(name)?;
+        self.aliases.borrow_mut().insert(name.to_string(), register);
+        Ok(())
+    }
+
+    pub(crate) fn forget_alias(&self, name: &str) -> Result<(), String> {
+        match self.aliases.borrow_mut().remove(name) {
+            Some(_) => Ok(()),
+            None => Err(format!("{} is not an alias", name)),
+        }
+    }
+
+    pub(crate) fn define_constant(&self, name: &str, value: i128, line: usize) -> Result<(), String> {
+        self.check_unused(name)?;
+        self.constants.borrow_mut().insert(name.to_string(), (value, line));
+        Ok(())
+    }
+
+    pub(crate) fn define_label(&self, name: &str) -> Result<(), String> {
+        self.check_not_constant_or_alias(name)?;
+        self.labels.borrow_mut().insert(name.to_string());
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use core::{fmt, cell::RefCell};
 
+use super::Symbols;
+
 #[derive(Debug, Clone, Copy)]
 pub struct TokenLocation<'a> {
     flie_name: &'a str,
@@ -9,7 +11,11 @@ pub struct TokenLocation<'a> {
 
 impl<'a> TokenLocation<'a> {
     pub fn new(file_name:&'a str,line: usize, column: usize) -> Self {
-        Self { line: line, column: column, flie_name: file_name }
+        Self { line, column, flie_name: file_name }
+    }
+
+    pub(crate) fn line(&self) -> usize {
+        self.line
     }
 }
 
@@ -23,59 +29,93 @@ pub struct Token<'a> {
     pub(crate) seq: &'a str,
     pub(crate) location: RefCell<TokenLocation<'a>>,
     pub(crate) len: RefCell<usize>,
+    pub(crate) symbols: &'a Symbols,
 }
 
 impl<'a> Token<'a> {
-    pub fn tokenize(s: &'a str, location:TokenLocation<'a>) -> Self {
+    pub fn tokenize(s: &'a str, location:TokenLocation<'a>, symbols: &'a Symbols) -> Self {
         let new = Self {
             seq: s,
             location: RefCell::new(location),
             len: RefCell::new(0),
+            symbols,
         };
 
+        new.skip_whitespace();
         *new.len.borrow_mut() = new.calculate_len();
         new
     }
 
     pub(crate) fn _inspect(&self) -> &'a str {
-        return &self.seq[self.location.borrow().column..self.location.borrow().column + *self.len.borrow()];
+        let column = self.location.borrow().column;
+        &self.seq[column..column + *self.len.borrow()]
     }
 
     fn calculate_len(&self) -> usize {
-        let mut len = 0;
-        while !self
-            .seq
-            .chars()
-            .nth(self.location.borrow().column + len)
-            .unwrap_or(' ')
-            .is_whitespace()
-        {
-            len += 1;
+        let rest = &self.seq[self.location.borrow().column..];
+        if is_comment(rest) {
+            return 0;
         }
-        len
+        if rest.starts_with('[') {
+            // memory operands may contain spaces: [rbp - 8]
+            return rest.find(']').map_or(rest.len(), |end| end + 1);
+        }
+        if rest.trim_start_matches('-').starts_with('(') {
+            // constant expressions may contain spaces: (4 * 8 + 1)
+            let mut depth = 0;
+            for (i, c) in rest.char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' if depth == 1 => return i + 1,
+                    ')' => depth -= 1,
+                    _ => (),
+                }
+            }
+            return rest.len();
+        }
+        if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            // string and character literals may contain spaces and escaped quotes: "say \"hi\"\n", ' '
+            let mut escaped = false;
+            for (i, c) in rest.char_indices().skip(1) {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    c if c == quote && !escaped => return i + 1,
+                    _ => escaped = false,
+                }
+            }
+            return rest.len();
+        }
+        rest.find(|c: char| c.is_whitespace() || c == ';' || c == '#').unwrap_or(rest.len())
+    }
+
+    fn skip_whitespace(&self) {
+        let column = self.location.borrow().column;
+        let rest = &self.seq[column..];
+        self.location.borrow_mut().column += rest.len() - rest.trim_start().len();
     }
 
     pub(crate) fn next(&self) {
         self.location.borrow_mut().column += *self.len.borrow();
-        while self
-            .seq
-            .chars()
-            .nth(self.location.borrow().column)
-            .unwrap_or('a')
-            .is_whitespace()
-        {
-            if self.seq.chars().nth(self.location.borrow().column).unwrap_or(' ') == '[' {
-                while self.seq.chars().nth(self.location.borrow().column).unwrap_or(']') == ']' {}
-                self.location.borrow_mut().column += 1;
-                break;
-            }
-            self.location.borrow_mut().column += 1;
-        }
+        self.skip_whitespace();
         *self.len.borrow_mut() = self.calculate_len();
-        // println!("{:?}", self)
     }
 
     pub fn is_end(&self) -> bool {
         *self.len.borrow() == 0
     }
+
+    // source text from an earlier token to the end of the current one
+    pub(crate) fn since(&self, start: TokenLocation<'a>) -> &'a str {
+        &self.seq[start.column..self.location.borrow().column + *self.len.borrow()]
+    }
+
+    // everything from the current token to the end of line
+    pub(crate) fn rest(&self) -> &'a str {
+        self.seq[self.location.borrow().column..].trim_end()
+    }
+}
+
+// line comments: -- comment, ; comment, # comment
+fn is_comment(s: &str) -> bool {
+    s.starts_with("--") || s.starts_with(';') || s.starts_with('#')
 }
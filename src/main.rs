@@ -1,10 +1,7 @@
 // this code is a rough sketch
 mod data;
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-use data::{AsmError, Token, TokenLocation, compile};
+use data::{AsmError, Program, Symbols};
 
 fn main() {
     match read_args() {
@@ -17,7 +14,7 @@ fn main() {
 fn read_args<'a>() -> Result<String, AsmError<'a>> {
     let args = std::env::args().skip(1).collect::<Vec<String>>();
     if args.len() != 1 {
-        return Err(AsmError::IOError(format!("args error")));
+        return Err(AsmError::IOError("args error".to_string()));
     }
     let path = &args[0];
     Ok(path.to_string())
@@ -25,20 +22,26 @@ fn read_args<'a>() -> Result<String, AsmError<'a>> {
 
 
 fn compile_file(file:&str) {
-    let mut code: String = String::new();
-    for (ln, line_result) in BufReader::new(File::open(file).unwrap()).lines().enumerate() {
-        let line = line_result.unwrap().clone();
-        let token = Box::new(Token::tokenize(&line, TokenLocation::new(file, ln, 0)));
-        match compile(&token, &mut code) {
-            Ok(_) => (),
-            Err(e) => {
+    let source = match std::fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("{}", AsmError::IOError(e.to_string()));
+            return;
+        }
+    };
+    let symbols = Symbols::default();
+    let code = match Program::parse(file, &source, &symbols).and_then(Program::codegen) {
+        Ok(code) => code,
+        Err(errors) => {
+            for e in errors {
                 println!("{}", e);
-                return;
             }
-        };
-    }
+            return;
+        }
+    };
 
-    println!(".intel_syntax noprefix");
-    println!(".global main");
-    println!("{}", code);
-}
\ No newline at end of file
+    for warning in code.warnings(&symbols) {
+        eprintln!("{}", warning);
+    }
+    print!("{}", code);
+}
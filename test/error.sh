@@ -0,0 +1,31 @@
+#! /bin/bash
+
+prepare() {
+    if [ ! -d "test/target" ]; then
+        mkdir test/target
+    fi
+}
+
+# the source must be rejected with a syntax error at line:column
+error() {
+    name="$1"
+    expected="$2"
+    printf 'main:\n%s\n' "$3" > test/target/$name.asm
+    actual=$(cargo run -q test/target/$name.asm 2>/dev/null | grep -m 1 -o "^syntax error \[test/target/$name.asm([0-9]*:[0-9]*)\]" | grep -o "[0-9]*:[0-9]*")
+    if [ "$actual" = "$expected" ]; then
+        echo "$name => Ok($actual)"
+    else
+        echo "$name => Err($expected expected, but got ${actual:-no error})"
+    fi
+}
+
+prepare
+error memory_to_memory 1:5 "move qword [rax] to qword [rbx]"
+error immediate_destination 1:12 "move rax to 1"
+error width_mismatch 1:5 "move eax to rbx"
+error immediate_too_wide 1:16 "multiply eax by 3000000000000"
+error xmm_in_integer 1:4 "add xmm0 to rax"
+error xmm_destination 1:12 "move rax to xmm1"
+error ambiguous_size 1:0 "add 1 to [rax]"
+error scan_xmm 1:9 "scan for xmm0 in rdi"
+echo Ok
@@ -12,8 +12,12 @@ run() {
 
 test() {
     expected="$2"
+    rm -f test/target/test$1
     run test/test$1.asm > test/target/test$1.S
-    cc -o test/target/test$1 test/target/test$1.S
+    if ! cc -o test/target/test$1 test/target/test$1.S; then
+        echo "test$1 => Err($expected expected, but cc failed)"
+        return
+    fi
     ./test/target/test$1
     actual="$?"
     if [ "$actual" = "$expected" ]; then
@@ -26,4 +30,26 @@ test() {
 prepare
 test 2 42
 test 3 55
+test 4 42
+test 5 42
+test 6 42
+test 7 42
+test 8 42
+test 9 42
+test 10 42
+test 11 42
+test 12 42
+test 13 42
+test 14 42
+test 15 42
+test 16 42
+test 17 42
+test 18 42
+test 19 42
+test 20 42
+test 21 42
+test 22 42
+test 23 42
+test 24 42
+test 25 42
 echo Ok
\ No newline at end of file
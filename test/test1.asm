@@ -1,6 +1,8 @@
+export main
+import printf
 main:
 add 1 to eax
-substract 1 from [ax]
+substract 1 from qword [rax]
 multiply eax by ebx
 divide eax
 move 1 to eax
@@ -0,0 +1,9 @@
+export main
+main:
+move -1 to rax
+move 42 to ecx
+move ecx to rax as zero-extended
+move al to ah
+move ah to cl
+move cl to eax as zero-extended
+return
@@ -0,0 +1,19 @@
+export main
+define six as quadwords 4618441417868443648
+define seven as quadwords 4619567317775286272
+define answer as quadwords 4631107791820423168
+
+main:
+load address of six to rax
+move [rax] to xmm0 as double-precision-float
+load address of seven to rax
+multiply xmm0 by [rax] as double-precision-float
+move xmm0 to xmm9 as double-precision-float
+add xmm9 to xmm9 as packed-doubles
+substract xmm0 from xmm9 as double-precision-float
+load address of answer to rax
+compare xmm9 to [rax] as double-precision-float
+move 0 to eax
+set al if =
+multiply eax by 42
+return
@@ -0,0 +1,11 @@
+export main
+main:
+move 6 to eax
+convert eax to xmm0 as double-precision-float
+move 7 to rcx
+convert rcx to xmm1 as single-precision-float
+convert xmm1 to xmm2 as double-precision-float
+multiply xmm0 by xmm2 as double-precision-float
+convert xmm0 to xmm3 as single-precision-float
+convert xmm3 to eax as single-precision-float
+return
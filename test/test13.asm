@@ -0,0 +1,24 @@
+export main
+main:
+move 1000 to rax
+move 23 to rcx
+push rcx
+divide rax by [rsp] as unsigned keep remainder in r8
+pop rcx
+compare r8 to 11
+jump to fail if !=
+compare rax to 43
+jump to fail if !=
+move 6 to eax
+move 7 to ecx
+multiply eax by ecx as unsigned
+move -85 to eax
+move 2 to ecx
+divide eax by ecx keep remainder in ebx
+compare ebx to -1
+jump to fail if !=
+negate eax
+return
+fail:
+move 1 to eax
+return
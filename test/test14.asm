@@ -0,0 +1,12 @@
+export main
+main:
+move -168 to eax
+shift-right eax by 2 as signed
+negate eax
+rotate-left eax by 4
+rotate-right eax by 4
+move 1 to ebx
+move 3 to cl
+shift-left eax by cl with ebx
+shift-right eax by 3
+return
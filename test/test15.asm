@@ -0,0 +1,21 @@
+export main
+reserve 1 quadword as counter
+
+main:
+load address of counter to rdi
+move 40 to qword [rdi]
+move 1 to rax
+atomically fetch-and-add rax to [rdi]
+atomically increment qword [rdi]
+move 42 to rax
+move 0 to rsi
+atomically compare-and-exchange [rdi] with rsi
+jump to fail if !=
+move 42 to rcx
+exchange rcx with [rdi]
+move rcx to rax
+add [rdi] to rax
+return
+fail:
+move 1 to eax
+return
@@ -0,0 +1,14 @@
+export main
+define message as string "the answer is *"
+reserve 32 bytes as buffer
+
+main:
+load address of buffer to rbx
+fill 4 quadwords at rbx with 0
+copy 16 bytes from message to rbx
+move rbx to rdx
+scan for 42 in rdx
+move rdi to rax
+substract rbx from rax
+add 27 to rax
+return
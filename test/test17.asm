@@ -0,0 +1,21 @@
+export main
+define digits as bytes '0' '1' 0x32
+
+main:
+move 0xffff_ffff_ffff_ffff to rax
+increment rax
+jump to fail if not-zero
+move 0b10_1010 to eax
+move 'A' to cl
+compare cl to 0o101
+jump to fail if !=
+move ' ' to cl
+compare cl to 32
+jump to fail if !=
+load address of digits to rdx
+compare byte [rdx + 0b10] to '2'
+jump to fail if !=
+return
+fail:
+move 1 to eax
+return
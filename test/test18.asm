@@ -0,0 +1,25 @@
+export main
+let SLOT be 8
+define table as quadwords 1 2 3 4 5
+define table_end as quadwords 0
+
+main:
+move (4 * 8 + 1) to rax
+load address of table to rbx
+add [rbx + 8*2] to rax
+move table_end - table to rcx
+shift-right rcx by 3
+add rcx to rax
+substract -(3 - 4) from rax
+load address of (table + 8 * 2) to rdx
+add [rdx - 8] to rax
+add [table + (2*8)] to rax
+substract [rdx + (4-2)*4 - 8] from rax
+add 4*8 to rax
+substract 32 from rax
+load address of (table + SLOT * 3) to rdx
+add [rdx] to rax
+move table_end - table - SLOT to rcx
+shift-right rcx by 3
+substract rcx from rax
+return
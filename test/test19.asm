@@ -0,0 +1,14 @@
+export main
+let ANSWER be 42
+let SLOT be 8
+define constant FRAME as SLOT * 2
+
+main:
+save rbp
+move rsp to rbp
+substract FRAME from rsp
+move ANSWER - 1 to qword [rbp - SLOT]
+move [rbp - SLOT] to rax
+increment rax
+leave
+return
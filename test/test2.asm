@@ -1,4 +1,5 @@
 
+export main
 main:
 move 42 to rax
 return
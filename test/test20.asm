@@ -0,0 +1,19 @@
+export main
+define numbers as quadwords 10 20 12
+
+main:
+call rcx index
+call rax total
+call rdx numbers_base
+move 0 to total
+move 0 to index
+load address of numbers to numbers_base
+loop:
+add [numbers_base + index*8] to total
+increment index
+compare index to 3
+jump to loop if <
+forget index
+forget total
+forget numbers_base
+return
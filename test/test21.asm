@@ -0,0 +1,11 @@
+export main
+main:
+load address of answer to rax
+move [rax] to rax
+jump to done if zero
+jump to done
+move 0 to eax
+done:
+return
+
+define answer as quadwords 42
@@ -0,0 +1,20 @@
+export main
+main:
+move 255 to bl
+move 65535 to cx
+move 4294967295 to edx
+move 4294967296 to rsi
+move -1 to rdi
+add rdi to rsi
+shift-right rsi by 27
+add cx to dx
+increment edx
+increment edx
+substract 244 from bl
+move bl to ebx as zero-extended
+move esi to eax
+add edx to eax
+add ebx to eax
+add -128 to rax
+add 128 to rax
+return
@@ -0,0 +1,13 @@
+export main
+define counter as quadwords 40
+define total as doublewords 0
+
+main:
+move counter to rax
+add 1 to qword counter
+add counter to rax
+move eax to total
+add 1 to total as doubleword
+move total to eax
+substract 40 from eax
+return
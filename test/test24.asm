@@ -0,0 +1,9 @@
+export main
+-- comments start with two dashes, a semicolon or a hash
+main:
+note: the result is built up in rax
+move 40 to rax ; trailing comments are skipped
+add 1 to rax -- whichever marker they use
+# a hash comment on its own line
+add 1 to rax
+return
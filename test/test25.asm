@@ -0,0 +1,13 @@
+export main
+main:
+move 0 to eax
+move 10 to ecx
+loop:
+increment eax by 5
+decrement ecx
+compare ecx to 0
+jump to loop if >
+decrement eax by 10
+increment eax
+increment eax
+return
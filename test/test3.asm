@@ -1,3 +1,4 @@
+export main
 main:
 move 0 to eax
 move 1 to ebx
@@ -0,0 +1,10 @@
+export main
+main:
+move 40 to rax
+move rax to [rsp-16]
+move 1 to rcx
+move 2 to rdx
+move rdx to [rsp+rcx*8-32]
+move [rsp - 16] to rax
+add [rsp-24] to rax
+return
\ No newline at end of file
@@ -0,0 +1,12 @@
+export main
+define message as string "hello, world\n"
+define table as quadwords 1 2 42
+reserve 64 bytes as buffer
+main:
+load address of table to rax
+move 2 to rcx
+move [rax+rcx*8] to rax
+load address of buffer to rdx
+move rax to [rdx+8]
+move [rdx + 8] to rax
+return
\ No newline at end of file
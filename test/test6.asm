@@ -0,0 +1,17 @@
+export main
+define counter as quadwords 0
+
+main:
+save rbp, rbx and r12
+move rsp to rbp
+move 40 to rbx
+push rbx
+push 2
+pop counter
+push counter
+pop r12
+pop rax
+add r12 to rax
+move rbp to rsp
+restore rbp, rbx and r12
+return
\ No newline at end of file
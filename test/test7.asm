@@ -0,0 +1,18 @@
+export main
+main:
+move 0 to eax
+move -1 to ebx
+compare ebx to 1
+jump to done if < as signed
+return
+done:
+jump to big if > as unsigned
+return
+big:
+move 42 to ecx
+move 0 to eax
+compare ecx to 42
+move ecx to eax if =
+set bl if !=
+add bl to al
+return
\ No newline at end of file
@@ -0,0 +1,18 @@
+export main
+main:
+move 0 to eax
+move 10 to ebx
+test ebx with ebx
+jump to nonzero if not-zero
+return
+nonzero:
+check bits of ebx with 4 -- 10 is 0b1010
+jump to bit if zero
+return
+bit:
+bit-test 1 of ebx
+jump to done if carry
+return
+done:
+move 42 to eax
+return
\ No newline at end of file
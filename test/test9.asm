@@ -0,0 +1,8 @@
+export main
+main:
+move -2 to bl
+move bl to eax as zero-extended
+move bl to ecx as signed
+add ecx to eax
+substract 210 from eax
+return
\ No newline at end of file